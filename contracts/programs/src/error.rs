@@ -40,4 +40,56 @@ pub enum ErrorCode {
     Unauthorized,
     #[msg("Marketplace not initialized")]
     MarketplaceNotInitialized,
+    #[msg("Invalid auction extension settings")]
+    InvalidAuctionExtension,
+    #[msg("Buy now not available")]
+    BuyNowNotAvailable,
+    #[msg("Invalid payment token account")]
+    InvalidPaymentAccount,
+    #[msg("Offer expired")]
+    OfferExpired,
+    #[msg("Collection bid already filled")]
+    CollectionBidFilled,
+    #[msg("Card does not match bid")]
+    CardDoesNotMatchBid,
+    #[msg("Invalid card metadata")]
+    InvalidMetadata,
+    #[msg("Invalid creator account")]
+    InvalidCreatorAccount,
+    #[msg("Marketplace is paused")]
+    MarketplacePaused,
+    #[msg("Listing expired")]
+    ListingExpired,
+    #[msg("Invalid bundle")]
+    InvalidBundle,
+    #[msg("Invalid swap")]
+    InvalidSwap,
+    #[msg("Listing reserved for another buyer")]
+    ListingReserved,
+    #[msg("Sweep exceeds max total price")]
+    SweepPriceExceeded,
+    #[msg("Auction already has bids")]
+    AuctionHasBids,
+    #[msg("Bid increment outside marketplace limits")]
+    InvalidBidIncrement,
+    #[msg("Not in the required sealed-bid phase")]
+    SealedBidPhase,
+    #[msg("Reveal does not match commitment")]
+    InvalidReveal,
+    #[msg("Invalid rental terms")]
+    InvalidRentalTerms,
+    #[msg("Card is currently rented")]
+    RentalActive,
+    #[msg("Invalid collection metadata")]
+    InvalidCollectionMetadata,
+    #[msg("Minimum next bid exceeds the maximum amount")]
+    BidOverflow,
+    #[msg("Mint request randomness is not available yet")]
+    MintRequestPending,
+    #[msg("Invalid mint request")]
+    InvalidMintRequest,
+    #[msg("Bid meets the buy-now price; use buy_now")]
+    BidAtBuyNowPrice,
+    #[msg("Reserve must be revealed until the reveal window closes")]
+    ReserveNotRevealed,
 }
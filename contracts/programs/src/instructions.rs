@@ -2,8 +2,12 @@ pub mod initialize;
 pub mod mint_card;
 pub mod marketplace;
 pub mod card_management;
+pub mod swap;
+pub mod rental;
 
 pub use initialize::*;
 pub use mint_card::*;
 pub use marketplace::*;
 pub use card_management::*;
+pub use swap::*;
+pub use rental::*;
@@ -1,5 +1,12 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
+use anchor_lang::system_program;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount, Transfer, transfer};
+use anchor_spl::metadata::{
+    create_master_edition_v3, create_metadata_accounts_v3, CreateMasterEditionV3, CreateMetadataAccountsV3, Metadata,
+    MetadataAccount,
+};
+use anchor_spl::metadata::mpl_token_metadata::types::{CollectionDetails, DataV2};
+use anchor_spl::associated_token::AssociatedToken;
 
 use crate::state::*;
 use crate::error::ErrorCode;
@@ -53,8 +60,56 @@ pub struct TransferCard<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+#[instruction(card_id: u64)]
+pub struct MigrateCard<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    /// Receives the rent of the legacy card account
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    
+    /// CHECK: Card in the legacy layout at its card-id address, parsed and closed by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"pookie_card", card_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub legacy_card: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + PookieCard::INIT_SPACE,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CardIndex::INIT_SPACE,
+        seeds = [b"card_index", card_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub card_index: Account<'info, CardIndex>,
+    
+    /// Collection the card was minted into, as recorded in its metadata
+    pub collection: Account<'info, CardCollection>,
+    
+    /// Card metadata, read for the collection
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct LevelUpCard<'info> {
+    /// Card owner, or its renter while usage rights are active
     #[account(mut)]
     pub owner: Signer<'info>,
     
@@ -72,6 +127,12 @@ pub struct LevelUpCard<'info> {
     )]
     pub user_stats: Account<'info, UserStats>,
     
+    #[account(
+        seeds = [b"usage_rights", card_mint.key().as_ref()],
+        bump
+    )]
+    pub usage_rights: Option<Account<'info, UsageRights>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
 }
 
@@ -126,10 +187,117 @@ pub struct CreateCollection<'info> {
     #[account(mut)]
     pub authority: Signer<'info>,
     
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"collection_mint", collection.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = collection,
+        mint::freeze_authority = collection,
+    )]
+    pub collection_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = collection,
+    )]
+    pub collection_token_account: Box<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Collection NFT metadata, created and checked by the token metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Collection NFT master edition, created and checked by the token metadata program
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateCollection<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    /// CHECK: Collection in the legacy layout, parsed and rewritten by the handler
+    #[account(
+        mut,
+        owner = crate::ID,
+        seeds = [b"collection", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttachCollectionNft<'info> {
+    #[account(
+        mut,
+        seeds = [b"collection", authority.key().as_ref()],
+        bump,
+        constraint = collection.collection_mint == Pubkey::default() @ ErrorCode::InvalidCollectionMetadata
+    )]
+    pub collection: Account<'info, CardCollection>,
+    
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = authority,
+        seeds = [b"collection_mint", collection.key().as_ref()],
+        bump,
+        mint::decimals = 0,
+        mint::authority = collection,
+        mint::freeze_authority = collection,
+    )]
+    pub collection_mint: Box<Account<'info, Mint>>,
+    
+    #[account(
+        init,
+        payer = authority,
+        associated_token::mint = collection_mint,
+        associated_token::authority = collection,
+    )]
+    pub collection_token_account: Box<Account<'info, TokenAccount>>,
+    
+    /// CHECK: Collection NFT metadata, created and checked by the token metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Collection NFT master edition, created and checked by the token metadata program
+    #[account(mut)]
+    pub collection_master_edition: UncheckedAccount<'info>,
+    
+    pub token_program: Program<'info, Token>,
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
 }
 
-pub fn transfer_card(ctx: Context<TransferCard>) -> Result<()> {
+#[derive(Accounts)]
+pub struct UpdateCollection<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"collection", authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, CardCollection>,
+}
+
+pub(crate) fn transfer_card(ctx: Context<TransferCard>) -> Result<()> {
     let clock = Clock::get()?;
     
     require!(
@@ -167,11 +335,79 @@ pub fn transfer_card(ctx: Context<TransferCard>) -> Result<()> {
     Ok(())
 }
 
-pub fn level_up_card(ctx: Context<LevelUpCard>) -> Result<()> {
+pub(crate) fn migrate_card(ctx: Context<MigrateCard>, card_id: u64) -> Result<()> {
+    let legacy_info = ctx.accounts.legacy_card.to_account_info();
+    let legacy = {
+        let data = legacy_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *PookieCard::DISCRIMINATOR,
+            ErrorCode::CardNotFound
+        );
+        LegacyPookieCard::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::CardNotFound)?
+    };
+    
+    require!(legacy.card_id == card_id, ErrorCode::CardNotFound);
+    require!(legacy.mint == ctx.accounts.card_mint.key(), ErrorCode::CardNotFound);
+    require!(legacy.owner == ctx.accounts.owner.key(), ErrorCode::NotCardOwner);
+    
+    // Legacy cards named their collection PDA in their metadata
+    require!(
+        ctx.accounts.metadata.mint == ctx.accounts.card_mint.key()
+            && ctx
+                .accounts
+                .metadata
+                .collection
+                .as_ref()
+                .is_some_and(|collection| collection.key == ctx.accounts.collection.key()),
+        ErrorCode::CollectionNotFound
+    );
+    
+    // Move the card to its mint-derived address
+    ctx.accounts.pookie_card.set_inner(PookieCard {
+        mint: legacy.mint,
+        owner: legacy.owner,
+        card_id: legacy.card_id,
+        name: legacy.name,
+        rarity: legacy.rarity,
+        element: legacy.element,
+        attack: legacy.attack,
+        defense: legacy.defense,
+        health: legacy.health,
+        special_ability: legacy.special_ability,
+        level: legacy.level,
+        experience: legacy.experience,
+        created_at: legacy.created_at,
+        last_updated: legacy.last_updated,
+        is_listed: legacy.is_listed,
+        listing_price: legacy.listing_price,
+        collection: ctx.accounts.collection.key(),
+    });
+    
+    // Close the legacy account, returning its rent to the owner
+    let legacy_lamports = legacy_info.lamports();
+    legacy_info.sub_lamports(legacy_lamports)?;
+    ctx.accounts.owner.add_lamports(legacy_lamports)?;
+    legacy_info.assign(&System::id());
+    legacy_info.resize(0)?;
+    
+    // Index the card id to its mint
+    let card_index = &mut ctx.accounts.card_index;
+    card_index.card_id = card_id;
+    card_index.mint = ctx.accounts.card_mint.key();
+    
+    Ok(())
+}
+
+pub(crate) fn level_up_card(ctx: Context<LevelUpCard>) -> Result<()> {
     let clock = Clock::get()?;
     
     require!(
-        ctx.accounts.pookie_card.owner == ctx.accounts.owner.key(),
+        ctx.accounts.pookie_card.is_controlled_by(
+            ctx.accounts.owner.key(),
+            ctx.accounts.usage_rights.as_deref(),
+            clock.unix_timestamp,
+        ),
         ErrorCode::NotCardOwner
     );
     
@@ -216,7 +452,7 @@ pub fn level_up_card(ctx: Context<LevelUpCard>) -> Result<()> {
     Ok(())
 }
 
-pub fn add_experience(ctx: Context<AddExperience>, amount: u32) -> Result<()> {
+pub(crate) fn add_experience(ctx: Context<AddExperience>, amount: u32) -> Result<()> {
     let clock = Clock::get()?;
     
     require!(amount > 0, ErrorCode::InvalidLevel);
@@ -232,7 +468,7 @@ pub fn add_experience(ctx: Context<AddExperience>, amount: u32) -> Result<()> {
     Ok(())
 }
 
-pub fn update_card_stats(
+pub(crate) fn update_card_stats(
     ctx: Context<UpdateCardStats>,
     new_attack: Option<u8>,
     new_defense: Option<u8>,
@@ -264,17 +500,26 @@ pub fn update_card_stats(
     Ok(())
 }
 
-pub fn create_collection(
+pub(crate) fn create_collection(
     ctx: Context<CreateCollection>,
     name: String,
     description: String,
     max_supply: Option<u64>,
+    base_uri: String,
+    symbol: String,
+    seller_fee_basis_points: u16,
 ) -> Result<()> {
     let clock = Clock::get()?;
     
     require!(!name.is_empty(), ErrorCode::InvalidLevel);
-    require!(name.len() <= 50, ErrorCode::InvalidLevel);
+    require!(name.len() <= 32, ErrorCode::InvalidLevel); // Metaplex name limit
     require!(description.len() <= 200, ErrorCode::InvalidLevel);
+    require!(
+        !base_uri.is_empty() && base_uri.len() <= CardCollection::MAX_BASE_URI_LEN,
+        ErrorCode::InvalidCollectionMetadata
+    );
+    require!(symbol.len() <= CardCollection::MAX_SYMBOL_LEN, ErrorCode::InvalidCollectionMetadata);
+    require!(seller_fee_basis_points <= 10000, ErrorCode::InvalidCollectionMetadata);
     
     let collection = &mut ctx.accounts.collection;
     collection.authority = ctx.accounts.authority.key();
@@ -284,6 +529,268 @@ pub fn create_collection(
     collection.max_supply = max_supply;
     collection.created_at = clock.unix_timestamp;
     collection.is_active = true;
+    collection.rarity_weights = CardCollection::DEFAULT_RARITY_WEIGHTS;
+    collection.minter = None;
+    collection.base_uri = base_uri;
+    collection.symbol = symbol;
+    collection.seller_fee_basis_points = seller_fee_basis_points;
+    collection.collection_mint = ctx.accounts.collection_mint.key();
+    
+    let authority_key = ctx.accounts.authority.key();
+    let collection_seeds: &[&[&[u8]]] = &[&[b"collection", authority_key.as_ref(), &[ctx.bumps.collection]]];
+    
+    // Mint the collection NFT to the collection PDA
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            to: ctx.accounts.collection_token_account.to_account_info(),
+            authority: ctx.accounts.collection.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    mint_to(cpi_ctx, 1)?;
+    
+    // Create collection metadata, sized so cards can be verified into it
+    let metadata_data = DataV2 {
+        name: ctx.accounts.collection.name.clone(),
+        symbol: ctx.accounts.collection.symbol.clone(),
+        uri: ctx.accounts.collection.collection_uri(),
+        seller_fee_basis_points: ctx.accounts.collection.seller_fee_basis_points,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.collection_metadata.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            mint_authority: ctx.accounts.collection.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    create_metadata_accounts_v3(cpi_ctx, metadata_data, true, true, Some(CollectionDetails::V1 { size: 0 }))?;
+    
+    // Create master edition
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        CreateMasterEditionV3 {
+            edition: ctx.accounts.collection_master_edition.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
+            mint_authority: ctx.accounts.collection.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            metadata: ctx.accounts.collection_metadata.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    create_master_edition_v3(cpi_ctx, Some(0))?;
+    
+    Ok(())
+}
+
+pub(crate) fn migrate_collection(
+    ctx: Context<MigrateCollection>,
+    base_uri: String,
+    symbol: String,
+    seller_fee_basis_points: u16,
+) -> Result<()> {
+    require!(
+        !base_uri.is_empty() && base_uri.len() <= CardCollection::MAX_BASE_URI_LEN,
+        ErrorCode::InvalidCollectionMetadata
+    );
+    require!(symbol.len() <= CardCollection::MAX_SYMBOL_LEN, ErrorCode::InvalidCollectionMetadata);
+    require!(seller_fee_basis_points <= 10000, ErrorCode::InvalidCollectionMetadata);
+    
+    let collection_info = ctx.accounts.collection.to_account_info();
+    let space = 8 + CardCollection::INIT_SPACE;
+    
+    // Collections created in the current layout are already full size
+    require!(collection_info.data_len() < space, ErrorCode::InvalidCollectionMetadata);
+    
+    let legacy = {
+        let data = collection_info.try_borrow_data()?;
+        require!(
+            data.len() >= 8 && data[..8] == *CardCollection::DISCRIMINATOR,
+            ErrorCode::CollectionNotFound
+        );
+        LegacyCardCollection::deserialize(&mut &data[8..])
+            .map_err(|_| ErrorCode::CollectionNotFound)?
+    };
+    
+    // Top up rent for the larger account, then grow it
+    let rent_due = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(collection_info.lamports());
+    if rent_due > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: collection_info.clone(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, rent_due)?;
+    }
+    
+    collection_info.resize(space)?;
+    
+    // Carry the legacy fields over and default the rest; the collection NFT is attached separately
+    let collection = CardCollection {
+        authority: legacy.authority,
+        name: legacy.name,
+        description: legacy.description,
+        total_cards: legacy.total_cards,
+        max_supply: legacy.max_supply,
+        created_at: legacy.created_at,
+        is_active: legacy.is_active,
+        rarity_weights: CardCollection::DEFAULT_RARITY_WEIGHTS,
+        minter: None,
+        base_uri,
+        symbol,
+        seller_fee_basis_points,
+        collection_mint: Pubkey::default(),
+    };
+    
+    let mut data = collection_info.try_borrow_mut_data()?;
+    let mut writer: &mut [u8] = &mut data;
+    collection.try_serialize(&mut writer)?;
+    
+    Ok(())
+}
+
+pub(crate) fn attach_collection_nft(ctx: Context<AttachCollectionNft>) -> Result<()> {
+    require!(
+        ctx.accounts.collection.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let authority_key = ctx.accounts.authority.key();
+    let collection_seeds: &[&[&[u8]]] = &[&[b"collection", authority_key.as_ref(), &[ctx.bumps.collection]]];
+    
+    // Mint the collection NFT to the collection PDA
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            to: ctx.accounts.collection_token_account.to_account_info(),
+            authority: ctx.accounts.collection.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    mint_to(cpi_ctx, 1)?;
+    
+    // Create collection metadata, sized so cards can be verified into it
+    let metadata_data = DataV2 {
+        name: ctx.accounts.collection.name.clone(),
+        symbol: ctx.accounts.collection.symbol.clone(),
+        uri: ctx.accounts.collection.collection_uri(),
+        seller_fee_basis_points: ctx.accounts.collection.seller_fee_basis_points,
+        creators: None,
+        collection: None,
+        uses: None,
+    };
+    
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        CreateMetadataAccountsV3 {
+            metadata: ctx.accounts.collection_metadata.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            mint_authority: ctx.accounts.collection.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    create_metadata_accounts_v3(cpi_ctx, metadata_data, true, true, Some(CollectionDetails::V1 { size: 0 }))?;
+    
+    // Create master edition
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        CreateMasterEditionV3 {
+            edition: ctx.accounts.collection_master_edition.to_account_info(),
+            mint: ctx.accounts.collection_mint.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
+            mint_authority: ctx.accounts.collection.to_account_info(),
+            payer: ctx.accounts.authority.to_account_info(),
+            metadata: ctx.accounts.collection_metadata.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+            rent: ctx.accounts.rent.to_account_info(),
+        },
+        collection_seeds,
+    );
+    
+    create_master_edition_v3(cpi_ctx, Some(0))?;
+    
+    let collection = &mut ctx.accounts.collection;
+    collection.collection_mint = ctx.accounts.collection_mint.key();
+    
+    Ok(())
+}
+
+pub(crate) fn update_collection(
+    ctx: Context<UpdateCollection>,
+    rarity_weights: Option<[u16; 6]>,
+    minter: Option<Option<Pubkey>>,
+    base_uri: Option<String>,
+    symbol: Option<String>,
+    seller_fee_basis_points: Option<u16>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.collection.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let collection = &mut ctx.accounts.collection;
+    
+    if let Some(rarity_weights) = rarity_weights {
+        require!(
+            rarity_weights.iter().any(|weight| *weight > 0),
+            ErrorCode::InvalidRarity
+        );
+        collection.rarity_weights = rarity_weights;
+    }
+    
+    if let Some(minter) = minter {
+        collection.minter = minter;
+    }
+    
+    // Metadata changes apply to cards minted from now on
+    if let Some(base_uri) = base_uri {
+        require!(
+            !base_uri.is_empty() && base_uri.len() <= CardCollection::MAX_BASE_URI_LEN,
+            ErrorCode::InvalidCollectionMetadata
+        );
+        collection.base_uri = base_uri;
+    }
+    
+    if let Some(symbol) = symbol {
+        require!(symbol.len() <= CardCollection::MAX_SYMBOL_LEN, ErrorCode::InvalidCollectionMetadata);
+        collection.symbol = symbol;
+    }
+    
+    if let Some(seller_fee_basis_points) = seller_fee_basis_points {
+        require!(seller_fee_basis_points <= 10000, ErrorCode::InvalidCollectionMetadata);
+        collection.seller_fee_basis_points = seller_fee_basis_points;
+    }
     
     Ok(())
 }
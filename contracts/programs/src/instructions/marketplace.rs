@@ -1,6 +1,9 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Token, TokenAccount, Transfer, transfer};
-use anchor_spl::associated_token::AssociatedToken;
+use anchor_lang::system_program;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, Transfer, transfer};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+use anchor_spl::metadata::MetadataAccount;
+use solana_sha256_hasher::hashv;
 
 use crate::state::*;
 use crate::error::ErrorCode;
@@ -16,6 +19,13 @@ pub struct InitializeMarketplace<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
     #[account(mut)]
     pub authority: Signer<'info>,
     
@@ -53,6 +63,8 @@ pub struct ListCard<'info> {
     #[account(
         init,
         payer = seller,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
         token::mint = card_mint,
         token::authority = marketplace,
     )]
@@ -65,6 +77,9 @@ pub struct ListCard<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    /// Whitelisted SPL mint to price in. Omit to price in SOL.
+    pub accepted_payment_mint: Option<Account<'info, AcceptedPaymentMint>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -104,8 +119,10 @@ pub struct BuyCard<'info> {
     
     #[account(
         mut,
-        associated_token::mint = card_mint,
-        associated_token::authority = marketplace,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
@@ -116,6 +133,13 @@ pub struct BuyCard<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
     #[account(
         mut,
         seeds = [b"user_stats", seller.key().as_ref()],
@@ -130,6 +154,19 @@ pub struct BuyCard<'info> {
     )]
     pub buyer_stats: Account<'info, UserStats>,
     
+    /// SPL payment accounts, required only when the listing is priced in a token
+    #[account(mut)]
+    pub buyer_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub seller_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub treasury_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -165,8 +202,10 @@ pub struct CancelListing<'info> {
     
     #[account(
         mut,
-        associated_token::mint = card_mint,
-        associated_token::authority = marketplace,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
@@ -183,6 +222,155 @@ pub struct CancelListing<'info> {
     pub system_program: Program<'info, System>,
 }
 
+#[derive(Accounts)]
+pub struct SweepListings<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_stats: Account<'info, UserStats>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateListingPrice<'info> {
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        seeds = [b"listing", card_mint.key().as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, Listing>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+}
+
+#[derive(Accounts)]
+#[instruction(bundle_id: u64)]
+pub struct CreateBundleListing<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + BundleListing::INIT_SPACE,
+        seeds = [b"bundle", seller.key().as_ref(), bundle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyBundle<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"bundle", seller.key().as_ref(), bundle_listing.bundle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_stats: Account<'info, UserStats>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelBundleListing<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"bundle", seller.key().as_ref(), bundle_listing.bundle_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub bundle_listing: Account<'info, BundleListing>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
 #[derive(Accounts)]
 pub struct CreateAuction<'info> {
     #[account(mut)]
@@ -214,11 +402,20 @@ pub struct CreateAuction<'info> {
     #[account(
         init,
         payer = seller,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
         token::mint = card_mint,
         token::authority = marketplace,
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
+    #[account(
+        mut,
+        seeds = [b"auction_vault", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: SystemAccount<'info>,
+    
     #[account(
         mut,
         seeds = [b"marketplace"],
@@ -226,6 +423,9 @@ pub struct CreateAuction<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    /// Whitelisted SPL mint to price in. Omit to price in SOL.
+    pub accepted_payment_mint: Option<Account<'info, AcceptedPaymentMint>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -237,6 +437,10 @@ pub struct PlaceBid<'info> {
     #[account(mut)]
     pub bidder: Signer<'info>,
     
+    /// Current highest bidder, refunded when outbid. Omitted for the first bid.
+    #[account(mut)]
+    pub previous_bidder: Option<SystemAccount<'info>>,
+    
     #[account(
         mut,
         seeds = [b"auction", card_mint.key().as_ref()],
@@ -244,6 +448,13 @@ pub struct PlaceBid<'info> {
     )]
     pub auction: Account<'info, Auction>,
     
+    #[account(
+        mut,
+        seeds = [b"auction_vault", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: SystemAccount<'info>,
+    
     #[account(
         mut,
         seeds = [b"marketplace"],
@@ -251,12 +462,31 @@ pub struct PlaceBid<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    /// SPL payment accounts, required only when the auction is priced in a token
+    #[account(mut)]
+    pub bidder_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub auction_vault_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// CHECK: Previous bidder's associated token account for the payment mint, recreated if it was closed
+    #[account(mut)]
+    pub previous_bidder_payment_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Payment mint, checked against the auction
+    pub payment_mint: Option<UncheckedAccount<'info>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
 #[derive(Accounts)]
 pub struct EndAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
     #[account(mut)]
     pub seller: SystemAccount<'info>,
     
@@ -280,7 +510,7 @@ pub struct EndAuction<'info> {
     
     #[account(
         init_if_needed,
-        payer = winner,
+        payer = payer,
         associated_token::mint = card_mint,
         associated_token::authority = winner,
     )]
@@ -289,7 +519,16 @@ pub struct EndAuction<'info> {
     #[account(
         mut,
         associated_token::mint = card_mint,
-        associated_token::authority = marketplace,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
     )]
     pub escrow_token_account: Account<'info, TokenAccount>,
     
@@ -300,6 +539,20 @@ pub struct EndAuction<'info> {
     )]
     pub marketplace: Account<'info, Marketplace>,
     
+    #[account(
+        mut,
+        seeds = [b"auction_vault", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
     #[account(
         mut,
         seeds = [b"user_stats", seller.key().as_ref()],
@@ -314,39 +567,3190 @@ pub struct EndAuction<'info> {
     )]
     pub winner_stats: Account<'info, UserStats>,
     
+    /// SPL payment accounts, required only when the auction is priced in a token
+    #[account(mut)]
+    pub auction_vault_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub seller_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub treasury_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// CHECK: Winner's associated token account for the payment mint, recreated if it was closed
+    #[account(mut)]
+    pub winner_payment_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Payment mint, checked against the auction
+    pub payment_mint: Option<UncheckedAccount<'info>>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
     pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
     pub token_program: Program<'info, Token>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn initialize_marketplace(ctx: Context<InitializeMarketplace>, fee_percentage: u16) -> Result<()> {
-    let clock = Clock::get()?;
+#[derive(Accounts)]
+pub struct CancelAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
     
-    require!(fee_percentage <= 1000, ErrorCode::InvalidPrice); // Max 10%
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
     
-    let marketplace = &mut ctx.accounts.marketplace;
-    marketplace.authority = ctx.accounts.authority.key();
-    marketplace.fee_percentage = fee_percentage;
-    marketplace.total_volume = 0;
-    marketplace.total_sales = 0;
-    marketplace.created_at = clock.unix_timestamp;
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
     
-    Ok(())
-}
-
-pub fn list_card(ctx: Context<ListCard>, price: u64) -> Result<()> {
-    let clock = Clock::get()?;
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
     
-    require!(price > 0, ErrorCode::InvalidPrice);
-    require!(
-        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
-        ErrorCode::NotCardOwner
-    );
-    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
     
-    // Transfer token to escrow
-    let cpi_ctx = CpiContext::new(
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"auction_vault", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: SystemAccount<'info>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyNow<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    
+    /// Current highest bidder, refunded when the auction is bought out. Omitted if there are no bids.
+    #[account(mut)]
+    pub previous_bidder: Option<SystemAccount<'info>>,
+    
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction: Account<'info, Auction>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = card_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"auction_vault", card_mint.key().as_ref()],
+        bump
+    )]
+    pub auction_vault: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_stats: Account<'info, UserStats>,
+    
+    /// SPL payment accounts, required only when the auction is priced in a token
+    #[account(mut)]
+    pub buyer_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub seller_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub treasury_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub auction_vault_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    /// CHECK: Previous bidder's associated token account for the payment mint, recreated if it was closed
+    #[account(mut)]
+    pub previous_bidder_payment_account: Option<UncheckedAccount<'info>>,
+    
+    /// CHECK: Payment mint, checked against the auction
+    pub payment_mint: Option<UncheckedAccount<'info>>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSealedAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + SealedAuction::INIT_SPACE,
+        seeds = [b"sealed_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CommitSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + SealedBid::INIT_SPACE,
+        seeds = [b"sealed_bid", card_mint.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealSealedBid<'info> {
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+    
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", card_mint.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleSealedAuction<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    
+    /// Highest revealed bidder, or the seller when there is none
+    #[account(mut)]
+    pub winner: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+    
+    /// Winning bid, required only when a bid was revealed
+    #[account(
+        mut,
+        seeds = [b"sealed_bid", card_mint.key().as_ref(), winner.key().as_ref()],
+        bump
+    )]
+    pub winning_bid: Option<Account<'info, SealedBid>>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = card_mint,
+        associated_token::authority = winner,
+    )]
+    pub winner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", winner.key().as_ref()],
+        bump
+    )]
+    pub winner_stats: Account<'info, UserStats>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimSealedBid<'info> {
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"sealed_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub sealed_auction: Account<'info, SealedAuction>,
+    
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [b"sealed_bid", card_mint.key().as_ref(), bidder.key().as_ref()],
+        bump
+    )]
+    pub sealed_bid: Account<'info, SealedBid>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+}
+
+#[derive(Accounts)]
+pub struct CreateDutchAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init,
+        payer = seller,
+        space = 8 + DutchAuction::INIT_SPACE,
+        seeds = [b"dutch_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub dutch_auction: Account<'info, DutchAuction>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = seller,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyDutchAuction<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(mut)]
+    pub seller: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"dutch_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub dutch_auction: Account<'info, DutchAuction>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init_if_needed,
+        payer = buyer,
+        associated_token::mint = card_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_stats: Account<'info, UserStats>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelDutchAuction<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        close = seller,
+        seeds = [b"dutch_auction", card_mint.key().as_ref()],
+        bump
+    )]
+    pub dutch_auction: Account<'info, DutchAuction>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AddPaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + AcceptedPaymentMint::INIT_SPACE,
+        seeds = [b"payment_mint", payment_mint.key().as_ref()],
+        bump
+    )]
+    pub accepted_payment_mint: Account<'info, AcceptedPaymentMint>,
+    
+    pub payment_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RemovePaymentMint<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"payment_mint", accepted_payment_mint.mint.as_ref()],
+        bump
+    )]
+    pub accepted_payment_mint: Account<'info, AcceptedPaymentMint>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateMarketplace<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptMarketplaceAuthority<'info> {
+    pub new_authority: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+}
+
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub authority: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub destination: SystemAccount<'info>,
+    
+    /// Treasury token account to withdraw SPL fees from. Omit to withdraw SOL.
+    #[account(mut)]
+    pub treasury_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    #[account(mut)]
+    pub destination_payment_account: Option<Box<Account<'info, TokenAccount>>>,
+    
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MakeOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init,
+        payer = buyer,
+        space = 8 + Offer::INIT_SPACE,
+        seeds = [b"offer", card_mint.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelOffer<'info> {
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [b"offer", card_mint.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptOffer<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(mut)]
+    pub buyer: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        close = buyer,
+        seeds = [b"offer", card_mint.key().as_ref(), buyer.key().as_ref()],
+        bump
+    )]
+    pub offer: Account<'info, Offer>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = card_mint,
+        associated_token::authority = buyer,
+    )]
+    pub buyer_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", buyer.key().as_ref()],
+        bump
+    )]
+    pub buyer_stats: Account<'info, UserStats>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(bid_id: u64)]
+pub struct CreateCollectionBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub collection: Account<'info, CardCollection>,
+    
+    #[account(
+        init,
+        payer = bidder,
+        space = 8 + CollectionBid::INIT_SPACE,
+        seeds = [
+            b"collection_bid",
+            collection.key().as_ref(),
+            bidder.key().as_ref(),
+            bid_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+    
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelCollectionBid<'info> {
+    #[account(mut)]
+    pub bidder: Signer<'info>,
+    
+    #[account(
+        mut,
+        close = bidder,
+        seeds = [
+            b"collection_bid",
+            collection_bid.collection.as_ref(),
+            bidder.key().as_ref(),
+            collection_bid.bid_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+}
+
+#[derive(Accounts)]
+pub struct FillCollectionBid<'info> {
+    #[account(mut)]
+    pub seller: Signer<'info>,
+    
+    #[account(mut)]
+    pub bidder: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [
+            b"collection_bid",
+            collection_bid.collection.as_ref(),
+            bidder.key().as_ref(),
+            collection_bid.bid_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub collection_bid: Account<'info, CollectionBid>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = seller,
+    )]
+    pub seller_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init_if_needed,
+        payer = seller,
+        associated_token::mint = card_mint,
+        associated_token::authority = bidder,
+    )]
+    pub bidder_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", seller.key().as_ref()],
+        bump
+    )]
+    pub seller_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", bidder.key().as_ref()],
+        bump
+    )]
+    pub bidder_stats: Account<'info, UserStats>,
+    
+    /// Card metadata, read for creator royalties
+    pub metadata: Box<Account<'info, MetadataAccount>>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+pub(crate) fn initialize_marketplace(ctx: Context<InitializeMarketplace>, fee_percentage: u16) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(fee_percentage <= 1000, ErrorCode::InvalidPrice); // Max 10%
+    
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.authority = ctx.accounts.authority.key();
+    marketplace.fee_percentage = fee_percentage;
+    marketplace.bump = ctx.bumps.marketplace;
+    marketplace.total_volume = 0;
+    marketplace.total_sales = 0;
+    marketplace.total_royalties = 0;
+    marketplace.is_paused = false;
+    marketplace.pending_authority = None;
+    marketplace.min_bid_increment_bps = 100; // 1%
+    marketplace.max_bid_increment_bps = 5000; // 50%
+    marketplace.created_at = clock.unix_timestamp;
+    
+    // Fund the treasury up to rent exemption so any fee amount can be deposited
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let treasury_lamports = ctx.accounts.treasury.lamports();
+    if treasury_lamports < rent_exempt_minimum {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.authority.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, rent_exempt_minimum - treasury_lamports)?;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(
+        ctx.accounts.marketplace.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let accepted_payment_mint = &mut ctx.accounts.accepted_payment_mint;
+    accepted_payment_mint.mint = ctx.accounts.payment_mint.key();
+    accepted_payment_mint.added_at = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn remove_payment_mint(ctx: Context<RemovePaymentMint>) -> Result<()> {
+    require!(
+        ctx.accounts.marketplace.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    Ok(())
+}
+
+pub(crate) fn update_marketplace(
+    ctx: Context<UpdateMarketplace>,
+    fee_percentage: Option<u16>,
+    is_paused: Option<bool>,
+    new_authority: Option<Pubkey>,
+    min_bid_increment_bps: Option<u16>,
+    max_bid_increment_bps: Option<u16>,
+) -> Result<()> {
+    require!(
+        ctx.accounts.marketplace.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let marketplace = &mut ctx.accounts.marketplace;
+    
+    if let Some(fee_percentage) = fee_percentage {
+        require!(fee_percentage <= 1000, ErrorCode::InvalidPrice); // Max 10%
+        marketplace.fee_percentage = fee_percentage;
+    }
+    
+    if let Some(is_paused) = is_paused {
+        marketplace.is_paused = is_paused;
+    }
+    
+    // New authority only takes over once it accepts
+    if let Some(new_authority) = new_authority {
+        marketplace.pending_authority = Some(new_authority);
+    }
+    
+    if let Some(min_bid_increment_bps) = min_bid_increment_bps {
+        marketplace.min_bid_increment_bps = min_bid_increment_bps;
+    }
+    
+    if let Some(max_bid_increment_bps) = max_bid_increment_bps {
+        marketplace.max_bid_increment_bps = max_bid_increment_bps;
+    }
+    
+    require!(
+        marketplace.min_bid_increment_bps > 0
+            && marketplace.min_bid_increment_bps <= marketplace.max_bid_increment_bps
+            && marketplace.max_bid_increment_bps <= 10000,
+        ErrorCode::InvalidBidIncrement
+    );
+    
+    Ok(())
+}
+
+pub(crate) fn accept_marketplace_authority(ctx: Context<AcceptMarketplaceAuthority>) -> Result<()> {
+    require!(
+        ctx.accounts.marketplace.pending_authority == Some(ctx.accounts.new_authority.key()),
+        ErrorCode::Unauthorized
+    );
+    
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.authority = ctx.accounts.new_authority.key();
+    marketplace.pending_authority = None;
+    
+    Ok(())
+}
+
+pub(crate) fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+    require!(
+        ctx.accounts.marketplace.authority == ctx.accounts.authority.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(amount > 0, ErrorCode::InvalidPrice);
+    
+    let treasury_seeds: &[&[&[u8]]] = &[&[b"treasury", &[ctx.bumps.treasury]]];
+    
+    if let Some(treasury_payment_account) = ctx.accounts.treasury_payment_account.as_deref() {
+        // Withdraw SPL fees held by the treasury
+        require!(
+            treasury_payment_account.owner == ctx.accounts.treasury.key(),
+            ErrorCode::InvalidPaymentAccount
+        );
+        require!(treasury_payment_account.amount >= amount, ErrorCode::InsufficientFunds);
+        
+        let destination_payment_account = payment_account(
+            &ctx.accounts.destination_payment_account,
+            treasury_payment_account.mint,
+            ctx.accounts.destination.key(),
+        )?;
+        
+        transfer_payment_tokens(
+            &ctx.accounts.token_program,
+            treasury_payment_account,
+            destination_payment_account,
+            ctx.accounts.treasury.to_account_info(),
+            amount,
+            treasury_seeds,
+        )?;
+    } else {
+        // Keep the treasury rent exempt
+        let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+        let available = ctx.accounts.treasury.lamports().saturating_sub(rent_exempt_minimum);
+        require!(amount <= available, ErrorCode::InsufficientFunds);
+        
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.treasury.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+            },
+            treasury_seeds,
+        );
+        
+        system_program::transfer(cpi_ctx, amount)?;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn list_card(
+    ctx: Context<ListCard>,
+    price: u64,
+    expires_at: Option<i64>,
+    reserved_buyer: Option<Pubkey>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(price > 0, ErrorCode::InvalidPrice);
+    require!(
+        expires_at.is_none_or(|expires_at| expires_at > clock.unix_timestamp),
+        ErrorCode::ListingExpired
+    );
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    require!(
+        reserved_buyer != Some(ctx.accounts.seller.key()),
+        ErrorCode::ListingReserved
+    );
+    
+    // Transfer token to escrow
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Create listing
+    let listing = &mut ctx.accounts.listing;
+    listing.seller = ctx.accounts.seller.key();
+    listing.mint = ctx.accounts.card_mint.key();
+    listing.price = price;
+    listing.payment_mint = ctx.accounts.accepted_payment_mint.as_ref().map(|accepted| accepted.mint);
+    listing.expires_at = expires_at;
+    listing.reserved_buyer = reserved_buyer;
+    listing.created_at = clock.unix_timestamp;
+    listing.is_active = true;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = true;
+    pookie_card.listing_price = Some(price);
+    
+    Ok(())
+}
+
+pub(crate) fn buy_card<'info>(ctx: Context<'_, '_, 'info, 'info, BuyCard<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(ctx.accounts.listing.is_active, ErrorCode::NotListed);
+    require!(
+        !ctx.accounts.listing.is_expired(clock.unix_timestamp),
+        ErrorCode::ListingExpired
+    );
+    require!(
+        ctx.accounts
+            .listing
+            .reserved_buyer
+            .is_none_or(|reserved_buyer| reserved_buyer == ctx.accounts.buyer.key()),
+        ErrorCode::ListingReserved
+    );
+    require!(
+        ctx.accounts.listing.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.listing.seller,
+        ErrorCode::NotCardOwner
+    );
+    
+    let price = ctx.accounts.listing.price;
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let royalties = creator_royalties(
+        &ctx.accounts.metadata,
+        ctx.accounts.card_mint.key(),
+        price,
+        ctx.remaining_accounts,
+    )?;
+    let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    
+    match ctx.accounts.listing.payment_mint {
+        None => {
+            require!(ctx.accounts.buyer.lamports() >= price, ErrorCode::InsufficientFunds);
+            
+            // Pay creator royalties
+            for royalty in &royalties {
+                require!(
+                    royalty.account.key() == royalty.creator,
+                    ErrorCode::InvalidCreatorAccount
+                );
+                
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: royalty.account.clone(),
+                    },
+                );
+                
+                system_program::transfer(cpi_ctx, royalty.amount)?;
+            }
+            
+            // Pay the seller
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.seller.to_account_info(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, seller_amount)?;
+            
+            // Route marketplace fee to treasury
+            if fee > 0 {
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                );
+                
+                system_program::transfer(cpi_ctx, fee)?;
+            }
+        }
+        Some(payment_mint) => {
+            let buyer_payment_account = payment_account(
+                &ctx.accounts.buyer_payment_account,
+                payment_mint,
+                ctx.accounts.buyer.key(),
+            )?;
+            let seller_payment_account = payment_account(
+                &ctx.accounts.seller_payment_account,
+                payment_mint,
+                ctx.accounts.seller.key(),
+            )?;
+            let treasury_payment_account = payment_account(
+                &ctx.accounts.treasury_payment_account,
+                payment_mint,
+                ctx.accounts.treasury.key(),
+            )?;
+            
+            require!(buyer_payment_account.amount >= price, ErrorCode::InsufficientFunds);
+            
+            // Pay creator royalties
+            for royalty in &royalties {
+                let creator_payment_account = Account::<TokenAccount>::try_from(royalty.account)?;
+                require!(
+                    creator_payment_account.mint == payment_mint
+                        && creator_payment_account.owner == royalty.creator,
+                    ErrorCode::InvalidCreatorAccount
+                );
+                
+                transfer_payment_tokens(
+                    &ctx.accounts.token_program,
+                    buyer_payment_account,
+                    &creator_payment_account,
+                    ctx.accounts.buyer.to_account_info(),
+                    royalty.amount,
+                    &[],
+                )?;
+            }
+            
+            // Pay the seller
+            transfer_payment_tokens(
+                &ctx.accounts.token_program,
+                buyer_payment_account,
+                seller_payment_account,
+                ctx.accounts.buyer.to_account_info(),
+                seller_amount,
+                &[],
+            )?;
+            
+            // Route marketplace fee to treasury
+            if fee > 0 {
+                transfer_payment_tokens(
+                    &ctx.accounts.token_program,
+                    buyer_payment_account,
+                    treasury_payment_account,
+                    ctx.accounts.buyer.to_account_info(),
+                    fee,
+                    &[],
+                )?;
+            }
+        }
+    }
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Transfer token from escrow to buyer
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Update card ownership
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.owner = ctx.accounts.buyer.key();
+    pookie_card.is_listed = false;
+    pookie_card.listing_price = None;
+    pookie_card.last_updated = clock.unix_timestamp;
+    
+    // Volume and earnings stats are denominated in lamports, so token sales only count as sales
+    let (volume, earned, royalty_volume) = match ctx.accounts.listing.payment_mint {
+        None => (price, seller_amount, royalty_total),
+        Some(_) => (0, 0, 0),
+    };
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += volume;
+    marketplace.total_royalties += royalty_volume;
+    marketplace.total_sales += 1;
+    
+    // Update user stats
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.cards_owned -= 1;
+    seller_stats.total_earned += earned;
+    seller_stats.last_activity = clock.unix_timestamp;
+    
+    let buyer_stats = &mut ctx.accounts.buyer_stats;
+    buyer_stats.cards_owned += 1;
+    buyer_stats.total_spent += volume;
+    buyer_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn sweep_listings<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SweepListings<'info>>,
+    max_total_price: u64,
+    skip_invalid: bool,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    
+    let buyer_key = ctx.accounts.buyer.key();
+    let marketplace_key = ctx.accounts.marketplace.key();
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    let mut total_price: u64 = 0;
+    let mut total_royalties: u64 = 0;
+    let mut cards_bought: u64 = 0;
+    let mut offset = 0;
+    
+    // Remaining accounts per listing:
+    // [card_mint, pookie_card, listing, escrow_token_account, buyer_token_account, seller, seller_stats, metadata, creators...]
+    while offset < ctx.remaining_accounts.len() {
+        let accounts = ctx
+            .remaining_accounts
+            .get(offset..offset + 8)
+            .ok_or(ErrorCode::NotListed)?;
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let listing_info = &accounts[2];
+        let escrow_token_account = Account::<TokenAccount>::try_from(&accounts[3])?;
+        let buyer_token_account = &accounts[4];
+        let seller = &accounts[5];
+        let mut seller_stats = Account::<UserStats>::try_from(&accounts[6])?;
+        let metadata = Account::<MetadataAccount>::try_from(&accounts[7])?;
+        let creator_accounts = &ctx.remaining_accounts[offset + 8..];
+        offset += 8 + metadata.creators.as_ref().map_or(0, Vec::len);
+        
+        require!(pookie_card.mint == card_mint.key(), ErrorCode::NotListed);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::NotListed);
+        require!(
+            escrow_token_account.mint == card_mint.key() && escrow_token_account.owner == marketplace_key,
+            ErrorCode::NotListed
+        );
+        
+        // Listings that sold or changed since the transaction was built are skipped or fail the sweep
+        let listing = Account::<Listing>::try_from(listing_info)
+            .map_err(|_| error!(ErrorCode::NotListed))
+            .and_then(|listing| {
+                check_sweep_listing(&listing, &pookie_card, buyer_key, clock.unix_timestamp)?;
+                require!(
+                    total_price + listing.price <= max_total_price,
+                    ErrorCode::SweepPriceExceeded
+                );
+                Ok(listing)
+            });
+        let listing = match listing {
+            Ok(listing) => listing,
+            Err(_) if skip_invalid => continue,
+            Err(err) => return Err(err),
+        };
+        
+        require!(listing.mint == card_mint.key(), ErrorCode::NotListed);
+        require!(seller.key() == listing.seller, ErrorCode::Unauthorized);
+        require!(seller_stats.owner == listing.seller, ErrorCode::Unauthorized);
+        
+        let price = listing.price;
+        let fee = ctx.accounts.marketplace.calculate_fee(price);
+        let royalties = creator_royalties(&metadata, card_mint.key(), price, creator_accounts)?;
+        let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+        let seller_amount = price
+            .checked_sub(fee + royalty_total)
+            .ok_or(ErrorCode::InvalidPrice)?;
+        
+        // Pay creator royalties
+        for royalty in &royalties {
+            require!(
+                royalty.account.key() == royalty.creator,
+                ErrorCode::InvalidCreatorAccount
+            );
+            
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: royalty.account.clone(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, royalty.amount)?;
+        }
+        
+        // Pay the seller
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: seller.clone(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, seller_amount)?;
+        
+        // Route marketplace fee to treasury
+        if fee > 0 {
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, fee)?;
+        }
+        
+        // Create the buyer's token account if needed
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: buyer_token_account.clone(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token from escrow to buyer
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.to_account_info(),
+                to: buyer_token_account.clone(),
+                authority: ctx.accounts.marketplace.to_account_info(),
+            },
+            marketplace_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Close escrow and listing, returning their rent to seller
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.to_account_info(),
+                destination: seller.clone(),
+                authority: ctx.accounts.marketplace.to_account_info(),
+            },
+            marketplace_seeds,
+        );
+        
+        close_account(cpi_ctx)?;
+        listing.close(seller.clone())?;
+        
+        // Update card ownership
+        pookie_card.owner = buyer_key;
+        pookie_card.is_listed = false;
+        pookie_card.listing_price = None;
+        pookie_card.last_updated = clock.unix_timestamp;
+        pookie_card.exit(&crate::ID)?;
+        
+        // Update seller stats
+        seller_stats.cards_owned -= 1;
+        seller_stats.total_earned += seller_amount;
+        seller_stats.last_activity = clock.unix_timestamp;
+        seller_stats.exit(&crate::ID)?;
+        
+        total_price += price;
+        total_royalties += royalty_total;
+        cards_bought += 1;
+    }
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += total_price;
+    marketplace.total_royalties += total_royalties;
+    marketplace.total_sales += cards_bought;
+    
+    // Update user stats
+    let buyer_stats = &mut ctx.accounts.buyer_stats;
+    buyer_stats.cards_owned += cards_bought;
+    buyer_stats.total_spent += total_price;
+    buyer_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(ctx.accounts.listing.is_active, ErrorCode::NotListed);
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Transfer token back to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = false;
+    pookie_card.listing_price = None;
+    
+    Ok(())
+}
+
+pub(crate) fn update_listing_price(ctx: Context<UpdateListingPrice>, new_price: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(new_price > 0, ErrorCode::InvalidPrice);
+    require!(ctx.accounts.listing.is_active, ErrorCode::NotListed);
+    require!(
+        ctx.accounts.listing.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        !ctx.accounts.listing.is_expired(clock.unix_timestamp),
+        ErrorCode::ListingExpired
+    );
+    
+    let listing = &mut ctx.accounts.listing;
+    listing.price = new_price;
+    
+    // Keep card status in sync
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.listing_price = Some(new_price);
+    pookie_card.last_updated = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn create_bundle_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateBundleListing<'info>>,
+    bundle_id: u64,
+    price: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(price > 0, ErrorCode::InvalidPrice);
+    
+    // Remaining accounts: [card_mint, pookie_card, seller_token_account, escrow_token_account] per card
+    let card_accounts = ctx.remaining_accounts;
+    require!(card_accounts.len() % 4 == 0, ErrorCode::InvalidBundle);
+    let card_count = card_accounts.len() / 4;
+    require!(
+        (2..=BundleListing::MAX_CARDS).contains(&card_count),
+        ErrorCode::InvalidBundle
+    );
+    
+    let bundle_key = ctx.accounts.bundle_listing.key();
+    let mut mints = Vec::with_capacity(card_count);
+    
+    for accounts in card_accounts.chunks(4) {
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let seller_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        let escrow_token_account = &accounts[3];
+        
+        require!(pookie_card.mint == card_mint.key(), ErrorCode::InvalidBundle);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidBundle);
+        require!(!mints.contains(&card_mint.key()), ErrorCode::InvalidBundle);
+        require!(
+            pookie_card.owner == ctx.accounts.seller.key(),
+            ErrorCode::NotCardOwner
+        );
+        require!(!pookie_card.is_listed, ErrorCode::AlreadyListed);
+        require!(
+            seller_token_account.mint == card_mint.key()
+                && seller_token_account.owner == ctx.accounts.seller.key(),
+            ErrorCode::InvalidBundle
+        );
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&bundle_key, &card_mint.key()),
+            ErrorCode::InvalidBundle
+        );
+        
+        // Create the bundle's escrow account for this card
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.seller.to_account_info(),
+                associated_token: escrow_token_account.clone(),
+                authority: ctx.accounts.bundle_listing.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token to escrow
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: seller_token_account.to_account_info(),
+                to: escrow_token_account.clone(),
+                authority: ctx.accounts.seller.to_account_info(),
+            },
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Update card status
+        pookie_card.is_listed = true;
+        pookie_card.listing_price = None;
+        pookie_card.exit(&crate::ID)?;
+        
+        mints.push(card_mint.key());
+    }
+    
+    // Create bundle listing
+    let bundle_listing = &mut ctx.accounts.bundle_listing;
+    bundle_listing.seller = ctx.accounts.seller.key();
+    bundle_listing.bundle_id = bundle_id;
+    bundle_listing.mints = mints;
+    bundle_listing.price = price;
+    bundle_listing.created_at = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn buy_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, BuyBundle<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(
+        ctx.accounts.buyer.key() != ctx.accounts.bundle_listing.seller,
+        ErrorCode::Unauthorized
+    );
+    
+    let price = ctx.accounts.bundle_listing.price;
+    let mints = ctx.accounts.bundle_listing.mints.clone();
+    let card_count = mints.len() as u64;
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    
+    require!(ctx.accounts.buyer.lamports() >= price, ErrorCode::InsufficientFunds);
+    
+    let seller_key = ctx.accounts.seller.key();
+    let bundle_key = ctx.accounts.bundle_listing.key();
+    let bundle_id_bytes = ctx.accounts.bundle_listing.bundle_id.to_le_bytes();
+    let bundle_seeds: &[&[&[u8]]] = &[&[
+        b"bundle",
+        seller_key.as_ref(),
+        bundle_id_bytes.as_ref(),
+        &[ctx.bumps.bundle_listing],
+    ]];
+    
+    // Remaining accounts per card, in listing order:
+    // [card_mint, pookie_card, escrow_token_account, buyer_token_account, metadata, creators...]
+    let mut royalty_total: u64 = 0;
+    let mut offset = 0;
+    
+    for (index, mint) in mints.iter().enumerate() {
+        let accounts = ctx
+            .remaining_accounts
+            .get(offset..offset + 5)
+            .ok_or(ErrorCode::InvalidBundle)?;
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let escrow_token_account = &accounts[2];
+        let buyer_token_account = &accounts[3];
+        let metadata = Account::<MetadataAccount>::try_from(&accounts[4])?;
+        
+        require!(card_mint.key() == *mint, ErrorCode::InvalidBundle);
+        require!(pookie_card.mint == *mint, ErrorCode::InvalidBundle);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidBundle);
+        require!(pookie_card.owner == seller_key, ErrorCode::NotCardOwner);
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&bundle_key, mint),
+            ErrorCode::InvalidBundle
+        );
+        
+        // Royalties are charged on each card's equal share of the bundle price
+        let card_price = price / card_count + if index == 0 { price % card_count } else { 0 };
+        let royalties = creator_royalties(
+            &metadata,
+            *mint,
+            card_price,
+            &ctx.remaining_accounts[offset + 5..],
+        )?;
+        offset += 5 + metadata.creators.as_ref().map_or(0, Vec::len);
+        
+        // Pay creator royalties
+        for royalty in &royalties {
+            require!(
+                royalty.account.key() == royalty.creator,
+                ErrorCode::InvalidCreatorAccount
+            );
+            
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: royalty.account.clone(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, royalty.amount)?;
+            royalty_total += royalty.amount;
+        }
+        
+        // Create the buyer's token account if needed
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.buyer.to_account_info(),
+                associated_token: buyer_token_account.clone(),
+                authority: ctx.accounts.buyer.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token from escrow to buyer
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.clone(),
+                to: buyer_token_account.clone(),
+                authority: ctx.accounts.bundle_listing.to_account_info(),
+            },
+            bundle_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Close escrow and return its rent to seller
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.clone(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: ctx.accounts.bundle_listing.to_account_info(),
+            },
+            bundle_seeds,
+        );
+        
+        close_account(cpi_ctx)?;
+        
+        // Update card ownership
+        pookie_card.owner = ctx.accounts.buyer.key();
+        pookie_card.is_listed = false;
+        pookie_card.listing_price = None;
+        pookie_card.last_updated = clock.unix_timestamp;
+        pookie_card.exit(&crate::ID)?;
+    }
+    
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    
+    // Pay the seller
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.buyer.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, seller_amount)?;
+    
+    // Route marketplace fee to treasury
+    if fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, fee)?;
+    }
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += price;
+    marketplace.total_royalties += royalty_total;
+    marketplace.total_sales += 1;
+    
+    // Update user stats
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.cards_owned -= card_count;
+    seller_stats.total_earned += seller_amount;
+    seller_stats.last_activity = clock.unix_timestamp;
+    
+    let buyer_stats = &mut ctx.accounts.buyer_stats;
+    buyer_stats.cards_owned += card_count;
+    buyer_stats.total_spent += price;
+    buyer_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_bundle_listing<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CancelBundleListing<'info>>,
+) -> Result<()> {
+    let mints = ctx.accounts.bundle_listing.mints.clone();
+    
+    // Remaining accounts: [pookie_card, escrow_token_account, seller_token_account] per card, in listing order
+    require!(
+        ctx.remaining_accounts.len() == mints.len() * 3,
+        ErrorCode::InvalidBundle
+    );
+    
+    let seller_key = ctx.accounts.seller.key();
+    let bundle_key = ctx.accounts.bundle_listing.key();
+    let bundle_id_bytes = ctx.accounts.bundle_listing.bundle_id.to_le_bytes();
+    let bundle_seeds: &[&[&[u8]]] = &[&[
+        b"bundle",
+        seller_key.as_ref(),
+        bundle_id_bytes.as_ref(),
+        &[ctx.bumps.bundle_listing],
+    ]];
+    
+    for (mint, accounts) in mints.iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[0])?;
+        let escrow_token_account = &accounts[1];
+        let seller_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        
+        require!(pookie_card.mint == *mint, ErrorCode::InvalidBundle);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidBundle);
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&bundle_key, mint),
+            ErrorCode::InvalidBundle
+        );
+        require!(
+            seller_token_account.mint == *mint && seller_token_account.owner == seller_key,
+            ErrorCode::InvalidBundle
+        );
+        
+        // Transfer token back to seller
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.clone(),
+                to: seller_token_account.to_account_info(),
+                authority: ctx.accounts.bundle_listing.to_account_info(),
+            },
+            bundle_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Close escrow and return its rent to seller
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.clone(),
+                destination: ctx.accounts.seller.to_account_info(),
+                authority: ctx.accounts.bundle_listing.to_account_info(),
+            },
+            bundle_seeds,
+        );
+        
+        close_account(cpi_ctx)?;
+        
+        // Update card status
+        pookie_card.is_listed = false;
+        pookie_card.listing_price = None;
+        pookie_card.exit(&crate::ID)?;
+    }
+    
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn create_auction(
+    ctx: Context<CreateAuction>,
+    starting_price: u64,
+    duration: i64,
+    extension_window: i64,
+    max_extension: i64,
+    reserve_commitment: Option<[u8; 32]>,
+    buy_now_price: Option<u64>,
+    bid_increment: BidIncrement,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(starting_price > 0, ErrorCode::InvalidPrice);
+    require!(duration > 0, ErrorCode::InvalidPrice);
+    if let Some(buy_now_price) = buy_now_price {
+        require!(buy_now_price > starting_price, ErrorCode::InvalidPrice);
+    }
+    require!(
+        extension_window >= 0 && max_extension >= 0,
+        ErrorCode::InvalidAuctionExtension
+    );
+    require!(
+        ctx.accounts.marketplace.allows_bid_increment(&bid_increment, starting_price),
+        ErrorCode::InvalidBidIncrement
+    );
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    
+    // Transfer token to escrow
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Fund the bid vault up to rent exemption so any bid amount can be escrowed
+    let rent_exempt_minimum = Rent::get()?.minimum_balance(0);
+    let vault_lamports = ctx.accounts.auction_vault.lamports();
+    if vault_lamports < rent_exempt_minimum {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.seller.to_account_info(),
+                to: ctx.accounts.auction_vault.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, rent_exempt_minimum - vault_lamports)?;
+    }
+    
+    // Create auction
+    let auction = &mut ctx.accounts.auction;
+    auction.seller = ctx.accounts.seller.key();
+    auction.mint = ctx.accounts.card_mint.key();
+    auction.starting_price = starting_price;
+    auction.current_bid = None;
+    auction.current_bidder = None;
+    auction.end_time = clock.unix_timestamp + duration;
+    auction.created_at = clock.unix_timestamp;
+    auction.is_active = true;
+    auction.extension_window = extension_window;
+    auction.max_extension = max_extension;
+    auction.total_extension = 0;
+    auction.reserve_commitment = reserve_commitment;
+    auction.buy_now_price = buy_now_price;
+    auction.payment_mint = ctx.accounts.accepted_payment_mint.as_ref().map(|accepted| accepted.mint);
+    auction.bid_increment = bid_increment;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = true;
+    pookie_card.listing_price = Some(starting_price);
+    
+    Ok(())
+}
+
+pub(crate) fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(ctx.accounts.auction.is_active, ErrorCode::AuctionNotActive);
+    require!(clock.unix_timestamp < ctx.accounts.auction.end_time, ErrorCode::AuctionExpired);
+    require!(
+        ctx.accounts.bidder.key() != ctx.accounts.auction.seller,
+        ErrorCode::CannotBidOnOwnAuction
+    );
+    
+    // Paying the buy-now price settles immediately, so it goes through buy_now instead
+    require!(
+        ctx.accounts.auction.buy_now_price.is_none_or(|price| bid_amount < price),
+        ErrorCode::BidAtBuyNowPrice
+    );
+    
+    let min_bid = ctx.accounts.auction.min_next_bid()?;
+    if bid_amount < min_bid {
+        msg!("Bid of {} is below the required minimum of {}", bid_amount, min_bid);
+        return err!(ErrorCode::BidTooLow);
+    }
+    
+    let card_mint_key = ctx.accounts.card_mint.key();
+    let vault_seeds: &[&[&[u8]]] = &[&[
+        b"auction_vault",
+        card_mint_key.as_ref(),
+        &[ctx.bumps.auction_vault],
+    ]];
+    
+    // The bid being replaced, if any
+    let previous = match (ctx.accounts.auction.current_bid, ctx.accounts.auction.current_bidder) {
+        (Some(previous_bid), Some(previous_bidder)) => {
+            let previous_bidder_account = ctx
+                .accounts
+                .previous_bidder
+                .as_ref()
+                .ok_or(ErrorCode::Unauthorized)?;
+            require!(
+                previous_bidder_account.key() == previous_bidder,
+                ErrorCode::Unauthorized
+            );
+            Some((previous_bid, previous_bidder_account))
+        }
+        _ => None,
+    };
+    
+    match ctx.accounts.auction.payment_mint {
+        None => {
+            // Escrow the new bid in the auction vault
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bidder.to_account_info(),
+                    to: ctx.accounts.auction_vault.to_account_info(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, bid_amount)?;
+            
+            // Refund the bid being replaced
+            if let Some((previous_bid, previous_bidder_account)) = previous {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.auction_vault.to_account_info(),
+                        to: previous_bidder_account.to_account_info(),
+                    },
+                    vault_seeds,
+                );
+                
+                system_program::transfer(cpi_ctx, previous_bid)?;
+            }
+        }
+        Some(payment_mint) => {
+            let bidder_payment_account = payment_account(
+                &ctx.accounts.bidder_payment_account,
+                payment_mint,
+                ctx.accounts.bidder.key(),
+            )?;
+            let vault_payment_account = payment_account(
+                &ctx.accounts.auction_vault_payment_account,
+                payment_mint,
+                ctx.accounts.auction_vault.key(),
+            )?;
+            
+            // Escrow the new bid in the vault's token account
+            transfer_payment_tokens(
+                &ctx.accounts.token_program,
+                bidder_payment_account,
+                vault_payment_account,
+                ctx.accounts.bidder.to_account_info(),
+                bid_amount,
+                &[],
+            )?;
+            
+            // Refund the bid being replaced into the previous bidder's associated token account
+            if let Some((previous_bid, previous_bidder_account)) = previous {
+                let (previous_bidder_payment_account, payment_mint_account) = associated_payment_account(
+                    &ctx.accounts.previous_bidder_payment_account,
+                    &ctx.accounts.payment_mint,
+                    payment_mint,
+                    previous_bidder_account.key(),
+                )?;
+                
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: ctx.accounts.bidder.to_account_info(),
+                        associated_token: previous_bidder_payment_account.clone(),
+                        authority: previous_bidder_account.to_account_info(),
+                        mint: payment_mint_account.clone(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                );
+                
+                associated_token::create_idempotent(cpi_ctx)?;
+                
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vault_payment_account.to_account_info(),
+                        to: previous_bidder_payment_account.clone(),
+                        authority: ctx.accounts.auction_vault.to_account_info(),
+                    },
+                    vault_seeds,
+                );
+                
+                transfer(cpi_ctx, previous_bid)?;
+            }
+        }
+    }
+    
+    // Update auction
+    let auction = &mut ctx.accounts.auction;
+    auction.current_bid = Some(bid_amount);
+    auction.current_bidder = Some(ctx.accounts.bidder.key());
+    
+    // Anti-sniping: late bids push end_time out to a full extension window
+    let time_remaining = auction.end_time - clock.unix_timestamp;
+    if time_remaining < auction.extension_window {
+        let extension = (auction.extension_window - time_remaining)
+            .min(auction.max_extension - auction.total_extension);
+        auction.end_time += extension;
+        auction.total_extension += extension;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn end_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, EndAuction<'info>>,
+    reserve_reveal: Option<ReserveReveal>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Settlement stays open while paused so escrowed cards and bids are never stuck
+    require!(clock.unix_timestamp >= ctx.accounts.auction.end_time, ErrorCode::AuctionExpired);
+    require!(
+        ctx.accounts.auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let card_mint_key = ctx.accounts.card_mint.key();
+    let vault_seeds: &[&[&[u8]]] = &[&[
+        b"auction_vault",
+        card_mint_key.as_ref(),
+        &[ctx.bumps.auction_vault],
+    ]];
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    let winning_bid = match (ctx.accounts.auction.current_bid, ctx.accounts.auction.current_bidder) {
+        (Some(final_bid), Some(winner)) => {
+            require!(winner == ctx.accounts.winner.key(), ErrorCode::Unauthorized);
+            Some(final_bid)
+        }
+        _ => None,
+    };
+    
+    // A committed reserve is met only if revealed; once the reveal window passes without one it counts as unmet
+    let reserve_met = match (ctx.accounts.auction.reserve_commitment, winning_bid) {
+        (Some(commitment), Some(final_bid)) => match reserve_reveal {
+            Some(reveal) => {
+                require!(
+                    hashv(&[&reveal.price.to_le_bytes(), &reveal.salt]).to_bytes() == commitment,
+                    ErrorCode::InvalidReveal
+                );
+                final_bid >= reveal.price
+            }
+            None => {
+                require!(
+                    clock.unix_timestamp >= ctx.accounts.auction.end_time + Auction::RESERVE_REVEAL_WINDOW,
+                    ErrorCode::ReserveNotRevealed
+                );
+                false
+            }
+        },
+        _ => true,
+    };
+    
+    if let Some(final_bid) = winning_bid.filter(|_| reserve_met) {
+        let fee = ctx.accounts.marketplace.calculate_fee(final_bid);
+        let royalties = creator_royalties(
+            &ctx.accounts.metadata,
+            ctx.accounts.card_mint.key(),
+            final_bid,
+            ctx.remaining_accounts,
+        )?;
+        let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+        let seller_amount = final_bid
+            .checked_sub(fee + royalty_total)
+            .ok_or(ErrorCode::InvalidPrice)?;
+        
+        match ctx.accounts.auction.payment_mint {
+            None => {
+                // Pay creator royalties
+                for royalty in &royalties {
+                    require!(
+                        royalty.account.key() == royalty.creator,
+                        ErrorCode::InvalidCreatorAccount
+                    );
+                    
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.auction_vault.to_account_info(),
+                            to: royalty.account.clone(),
+                        },
+                        vault_seeds,
+                    );
+                    
+                    system_program::transfer(cpi_ctx, royalty.amount)?;
+                }
+                
+                // Route marketplace fee from the vault to treasury; the seller's share
+                // is paid out with the vault balance below
+                if fee > 0 {
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.auction_vault.to_account_info(),
+                            to: ctx.accounts.treasury.to_account_info(),
+                        },
+                        vault_seeds,
+                    );
+                    
+                    system_program::transfer(cpi_ctx, fee)?;
+                }
+            }
+            Some(payment_mint) => {
+                let vault_payment_account = payment_account(
+                    &ctx.accounts.auction_vault_payment_account,
+                    payment_mint,
+                    ctx.accounts.auction_vault.key(),
+                )?;
+                let seller_payment_account = payment_account(
+                    &ctx.accounts.seller_payment_account,
+                    payment_mint,
+                    ctx.accounts.seller.key(),
+                )?;
+                let treasury_payment_account = payment_account(
+                    &ctx.accounts.treasury_payment_account,
+                    payment_mint,
+                    ctx.accounts.treasury.key(),
+                )?;
+                
+                // Pay creator royalties
+                for royalty in &royalties {
+                    let creator_payment_account = Account::<TokenAccount>::try_from(royalty.account)?;
+                    require!(
+                        creator_payment_account.mint == payment_mint
+                            && creator_payment_account.owner == royalty.creator,
+                        ErrorCode::InvalidCreatorAccount
+                    );
+                    
+                    transfer_payment_tokens(
+                        &ctx.accounts.token_program,
+                        vault_payment_account,
+                        &creator_payment_account,
+                        ctx.accounts.auction_vault.to_account_info(),
+                        royalty.amount,
+                        vault_seeds,
+                    )?;
+                }
+                
+                // Pay the seller from the vault
+                transfer_payment_tokens(
+                    &ctx.accounts.token_program,
+                    vault_payment_account,
+                    seller_payment_account,
+                    ctx.accounts.auction_vault.to_account_info(),
+                    seller_amount,
+                    vault_seeds,
+                )?;
+                
+                // Route marketplace fee from the vault to treasury
+                if fee > 0 {
+                    transfer_payment_tokens(
+                        &ctx.accounts.token_program,
+                        vault_payment_account,
+                        treasury_payment_account,
+                        ctx.accounts.auction_vault.to_account_info(),
+                        fee,
+                        vault_seeds,
+                    )?;
+                }
+            }
+        }
+        
+        // Transfer token to winner
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.winner_token_account.to_account_info(),
+                authority: ctx.accounts.marketplace.to_account_info(),
+            },
+            marketplace_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Update card ownership
+        let pookie_card = &mut ctx.accounts.pookie_card;
+        pookie_card.owner = ctx.accounts.winner.key();
+        pookie_card.is_listed = false;
+        pookie_card.listing_price = None;
+        pookie_card.last_updated = clock.unix_timestamp;
+        
+        // Volume and earnings stats are denominated in lamports, so token sales only count as sales
+        let (volume, earned, royalty_volume) = match ctx.accounts.auction.payment_mint {
+            None => (final_bid, seller_amount, royalty_total),
+            Some(_) => (0, 0, 0),
+        };
+        
+        // Update marketplace stats
+        let marketplace = &mut ctx.accounts.marketplace;
+        marketplace.total_volume += volume;
+        marketplace.total_royalties += royalty_volume;
+        marketplace.total_sales += 1;
+        
+        // Update user stats
+        let seller_stats = &mut ctx.accounts.seller_stats;
+        seller_stats.cards_owned -= 1;
+        seller_stats.total_earned += earned;
+        seller_stats.last_activity = clock.unix_timestamp;
+        
+        let winner_stats = &mut ctx.accounts.winner_stats;
+        winner_stats.cards_owned += 1;
+        winner_stats.total_spent += volume;
+        winner_stats.last_activity = clock.unix_timestamp;
+    } else {
+        // Refund a top bid that fell short of the reserve
+        if let Some(final_bid) = winning_bid {
+            match ctx.accounts.auction.payment_mint {
+                None => {
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.auction_vault.to_account_info(),
+                            to: ctx.accounts.winner.to_account_info(),
+                        },
+                        vault_seeds,
+                    );
+                    
+                    system_program::transfer(cpi_ctx, final_bid)?;
+                }
+                Some(payment_mint) => {
+                    let vault_payment_account = payment_account(
+                        &ctx.accounts.auction_vault_payment_account,
+                        payment_mint,
+                        ctx.accounts.auction_vault.key(),
+                    )?;
+                    let (winner_payment_account, payment_mint_account) = associated_payment_account(
+                        &ctx.accounts.winner_payment_account,
+                        &ctx.accounts.payment_mint,
+                        payment_mint,
+                        ctx.accounts.winner.key(),
+                    )?;
+                    
+                    // Recreate the bidder's token account if it was closed
+                    let cpi_ctx = CpiContext::new(
+                        ctx.accounts.associated_token_program.to_account_info(),
+                        associated_token::Create {
+                            payer: ctx.accounts.payer.to_account_info(),
+                            associated_token: winner_payment_account.clone(),
+                            authority: ctx.accounts.winner.to_account_info(),
+                            mint: payment_mint_account.clone(),
+                            system_program: ctx.accounts.system_program.to_account_info(),
+                            token_program: ctx.accounts.token_program.to_account_info(),
+                        },
+                    );
+                    
+                    associated_token::create_idempotent(cpi_ctx)?;
+                    
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        Transfer {
+                            from: vault_payment_account.to_account_info(),
+                            to: winner_payment_account.clone(),
+                            authority: ctx.accounts.auction_vault.to_account_info(),
+                        },
+                        vault_seeds,
+                    );
+                    
+                    transfer(cpi_ctx, final_bid)?;
+                }
+            }
+        }
+        
+        // No sale, return to seller
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: ctx.accounts.escrow_token_account.to_account_info(),
+                to: ctx.accounts.seller_token_account.to_account_info(),
+                authority: ctx.accounts.marketplace.to_account_info(),
+            },
+            marketplace_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Update card status
+        let pookie_card = &mut ctx.accounts.pookie_card;
+        pookie_card.is_listed = false;
+        pookie_card.listing_price = None;
+    }
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Pay out the remaining vault lamports (SOL sale proceeds and rent deposit) to seller
+    let vault_lamports = ctx.accounts.auction_vault.lamports();
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.auction_vault.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        },
+        vault_seeds,
+    );
+    
+    system_program::transfer(cpi_ctx, vault_lamports)?;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+    require!(
+        ctx.accounts.auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(ctx.accounts.auction.is_active, ErrorCode::AuctionNotActive);
+    require!(ctx.accounts.auction.current_bid.is_none(), ErrorCode::AuctionHasBids);
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    let card_mint_key = ctx.accounts.card_mint.key();
+    let vault_seeds: &[&[&[u8]]] = &[&[
+        b"auction_vault",
+        card_mint_key.as_ref(),
+        &[ctx.bumps.auction_vault],
+    ]];
+    
+    // Transfer token back to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.seller_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Return the vault's rent deposit to seller
+    let vault_lamports = ctx.accounts.auction_vault.lamports();
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.auction_vault.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        },
+        vault_seeds,
+    );
+    
+    system_program::transfer(cpi_ctx, vault_lamports)?;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = false;
+    pookie_card.listing_price = None;
+    
+    Ok(())
+}
+
+pub(crate) fn buy_now<'info>(ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(ctx.accounts.auction.is_active, ErrorCode::AuctionNotActive);
+    require!(clock.unix_timestamp < ctx.accounts.auction.end_time, ErrorCode::AuctionExpired);
+    require!(
+        ctx.accounts.auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.buyer.key() != ctx.accounts.auction.seller,
+        ErrorCode::CannotBidOnOwnAuction
+    );
+    
+    let price = ctx
+        .accounts
+        .auction
+        .buy_now_price
+        .ok_or(ErrorCode::BuyNowNotAvailable)?;
+    require!(
+        ctx.accounts.auction.current_bid.is_none_or(|bid| bid < price),
+        ErrorCode::BuyNowNotAvailable
+    );
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let royalties = creator_royalties(
+        &ctx.accounts.metadata,
+        ctx.accounts.card_mint.key(),
+        price,
+        ctx.remaining_accounts,
+    )?;
+    let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    
+    match ctx.accounts.auction.payment_mint {
+        None => {
+            require!(ctx.accounts.buyer.lamports() >= price, ErrorCode::InsufficientFunds);
+            
+            // Pay creator royalties
+            for royalty in &royalties {
+                require!(
+                    royalty.account.key() == royalty.creator,
+                    ErrorCode::InvalidCreatorAccount
+                );
+                
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: royalty.account.clone(),
+                    },
+                );
+                
+                system_program::transfer(cpi_ctx, royalty.amount)?;
+            }
+            
+            // Pay the seller
+            let cpi_ctx = CpiContext::new(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.buyer.to_account_info(),
+                    to: ctx.accounts.seller.to_account_info(),
+                },
+            );
+            
+            system_program::transfer(cpi_ctx, seller_amount)?;
+            
+            // Route marketplace fee to treasury
+            if fee > 0 {
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.buyer.to_account_info(),
+                        to: ctx.accounts.treasury.to_account_info(),
+                    },
+                );
+                
+                system_program::transfer(cpi_ctx, fee)?;
+            }
+        }
+        Some(payment_mint) => {
+            let buyer_payment_account = payment_account(
+                &ctx.accounts.buyer_payment_account,
+                payment_mint,
+                ctx.accounts.buyer.key(),
+            )?;
+            let seller_payment_account = payment_account(
+                &ctx.accounts.seller_payment_account,
+                payment_mint,
+                ctx.accounts.seller.key(),
+            )?;
+            let treasury_payment_account = payment_account(
+                &ctx.accounts.treasury_payment_account,
+                payment_mint,
+                ctx.accounts.treasury.key(),
+            )?;
+            
+            require!(buyer_payment_account.amount >= price, ErrorCode::InsufficientFunds);
+            
+            // Pay creator royalties
+            for royalty in &royalties {
+                let creator_payment_account = Account::<TokenAccount>::try_from(royalty.account)?;
+                require!(
+                    creator_payment_account.mint == payment_mint
+                        && creator_payment_account.owner == royalty.creator,
+                    ErrorCode::InvalidCreatorAccount
+                );
+                
+                transfer_payment_tokens(
+                    &ctx.accounts.token_program,
+                    buyer_payment_account,
+                    &creator_payment_account,
+                    ctx.accounts.buyer.to_account_info(),
+                    royalty.amount,
+                    &[],
+                )?;
+            }
+            
+            // Pay the seller
+            transfer_payment_tokens(
+                &ctx.accounts.token_program,
+                buyer_payment_account,
+                seller_payment_account,
+                ctx.accounts.buyer.to_account_info(),
+                seller_amount,
+                &[],
+            )?;
+            
+            // Route marketplace fee to treasury
+            if fee > 0 {
+                transfer_payment_tokens(
+                    &ctx.accounts.token_program,
+                    buyer_payment_account,
+                    treasury_payment_account,
+                    ctx.accounts.buyer.to_account_info(),
+                    fee,
+                    &[],
+                )?;
+            }
+        }
+    }
+    
+    let card_mint_key = ctx.accounts.card_mint.key();
+    let vault_seeds: &[&[&[u8]]] = &[&[
+        b"auction_vault",
+        card_mint_key.as_ref(),
+        &[ctx.bumps.auction_vault],
+    ]];
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Refund the current top bid
+    if let (Some(previous_bid), Some(previous_bidder)) =
+        (ctx.accounts.auction.current_bid, ctx.accounts.auction.current_bidder)
+    {
+        let previous_bidder_account = ctx
+            .accounts
+            .previous_bidder
+            .as_ref()
+            .ok_or(ErrorCode::Unauthorized)?;
+        require!(
+            previous_bidder_account.key() == previous_bidder,
+            ErrorCode::Unauthorized
+        );
+        
+        match ctx.accounts.auction.payment_mint {
+            None => {
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.auction_vault.to_account_info(),
+                        to: previous_bidder_account.to_account_info(),
+                    },
+                    vault_seeds,
+                );
+                
+                system_program::transfer(cpi_ctx, previous_bid)?;
+            }
+            Some(payment_mint) => {
+                let vault_payment_account = payment_account(
+                    &ctx.accounts.auction_vault_payment_account,
+                    payment_mint,
+                    ctx.accounts.auction_vault.key(),
+                )?;
+                let (previous_bidder_payment_account, payment_mint_account) = associated_payment_account(
+                    &ctx.accounts.previous_bidder_payment_account,
+                    &ctx.accounts.payment_mint,
+                    payment_mint,
+                    previous_bidder,
+                )?;
+                
+                // Recreate the previous bidder's token account if it was closed
+                let cpi_ctx = CpiContext::new(
+                    ctx.accounts.associated_token_program.to_account_info(),
+                    associated_token::Create {
+                        payer: ctx.accounts.buyer.to_account_info(),
+                        associated_token: previous_bidder_payment_account.clone(),
+                        authority: previous_bidder_account.to_account_info(),
+                        mint: payment_mint_account.clone(),
+                        system_program: ctx.accounts.system_program.to_account_info(),
+                        token_program: ctx.accounts.token_program.to_account_info(),
+                    },
+                );
+                
+                associated_token::create_idempotent(cpi_ctx)?;
+                
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.token_program.to_account_info(),
+                    Transfer {
+                        from: vault_payment_account.to_account_info(),
+                        to: previous_bidder_payment_account.clone(),
+                        authority: ctx.accounts.auction_vault.to_account_info(),
+                    },
+                    vault_seeds,
+                );
+                
+                transfer(cpi_ctx, previous_bid)?;
+            }
+        }
+    }
+    
+    // Transfer token from escrow to buyer
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Return the vault rent deposit to seller
+    let vault_lamports = ctx.accounts.auction_vault.lamports();
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.auction_vault.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        },
+        vault_seeds,
+    );
+    
+    system_program::transfer(cpi_ctx, vault_lamports)?;
+    
+    // Update card ownership
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.owner = ctx.accounts.buyer.key();
+    pookie_card.is_listed = false;
+    pookie_card.listing_price = None;
+    pookie_card.last_updated = clock.unix_timestamp;
+    
+    // Volume and earnings stats are denominated in lamports, so token sales only count as sales
+    let (volume, earned, royalty_volume) = match ctx.accounts.auction.payment_mint {
+        None => (price, seller_amount, royalty_total),
+        Some(_) => (0, 0, 0),
+    };
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += volume;
+    marketplace.total_royalties += royalty_volume;
+    marketplace.total_sales += 1;
+    
+    // Update user stats
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.cards_owned -= 1;
+    seller_stats.total_earned += earned;
+    seller_stats.last_activity = clock.unix_timestamp;
+    
+    let buyer_stats = &mut ctx.accounts.buyer_stats;
+    buyer_stats.cards_owned += 1;
+    buyer_stats.total_spent += volume;
+    buyer_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn create_sealed_auction(
+    ctx: Context<CreateSealedAuction>,
+    min_price: u64,
+    commit_duration: i64,
+    reveal_duration: i64,
+    pricing: SealedBidPricing,
+    forfeit_unrevealed: bool,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(min_price > 0, ErrorCode::InvalidPrice);
+    require!(commit_duration > 0 && reveal_duration > 0, ErrorCode::SealedBidPhase);
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    
+    // Transfer token to escrow
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Create sealed auction
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.seller = ctx.accounts.seller.key();
+    sealed_auction.mint = ctx.accounts.card_mint.key();
+    sealed_auction.min_price = min_price;
+    sealed_auction.commit_end = clock.unix_timestamp + commit_duration;
+    sealed_auction.reveal_end = sealed_auction.commit_end + reveal_duration;
+    sealed_auction.pricing = pricing;
+    sealed_auction.forfeit_unrevealed = forfeit_unrevealed;
+    sealed_auction.highest_bid = None;
+    sealed_auction.highest_bidder = None;
+    sealed_auction.second_bid = None;
+    sealed_auction.open_bids = 0;
+    sealed_auction.created_at = clock.unix_timestamp;
+    sealed_auction.is_settled = false;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = true;
+    pookie_card.listing_price = Some(min_price);
+    
+    Ok(())
+}
+
+pub(crate) fn commit_sealed_bid(ctx: Context<CommitSealedBid>, commitment: [u8; 32], deposit: u64) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(
+        clock.unix_timestamp < ctx.accounts.sealed_auction.commit_end,
+        ErrorCode::SealedBidPhase
+    );
+    require!(
+        ctx.accounts.bidder.key() != ctx.accounts.sealed_auction.seller,
+        ErrorCode::CannotBidOnOwnAuction
+    );
+    
+    // The deposit caps the bid that can later be revealed, so it must at least cover the minimum
+    require!(deposit >= ctx.accounts.sealed_auction.min_price, ErrorCode::BidTooLow);
+    
+    // Escrow the deposit in the bid account itself
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.bidder.to_account_info(),
+            to: ctx.accounts.sealed_bid.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, deposit)?;
+    
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+    sealed_bid.bidder = ctx.accounts.bidder.key();
+    sealed_bid.mint = ctx.accounts.card_mint.key();
+    sealed_bid.commitment = commitment;
+    sealed_bid.deposit = deposit;
+    sealed_bid.revealed_amount = None;
+    sealed_bid.created_at = clock.unix_timestamp;
+    
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.open_bids += 1;
+    
+    Ok(())
+}
+
+pub(crate) fn reveal_sealed_bid(ctx: Context<RevealSealedBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(
+        clock.unix_timestamp >= ctx.accounts.sealed_auction.commit_end
+            && clock.unix_timestamp < ctx.accounts.sealed_auction.reveal_end,
+        ErrorCode::SealedBidPhase
+    );
+    require!(
+        ctx.accounts.sealed_bid.revealed_amount.is_none(),
+        ErrorCode::InvalidReveal
+    );
+    require!(
+        hashv(&[&amount.to_le_bytes(), &salt]).to_bytes() == ctx.accounts.sealed_bid.commitment,
+        ErrorCode::InvalidReveal
+    );
+    require!(amount >= ctx.accounts.sealed_auction.min_price, ErrorCode::BidTooLow);
+    require!(amount <= ctx.accounts.sealed_bid.deposit, ErrorCode::InsufficientFunds);
+    
+    let sealed_bid = &mut ctx.accounts.sealed_bid;
+    sealed_bid.revealed_amount = Some(amount);
+    
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.record_reveal(ctx.accounts.bidder.key(), amount);
+    
+    Ok(())
+}
+
+pub(crate) fn settle_sealed_auction<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SettleSealedAuction<'info>>,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    // Settlement stays open while paused so escrowed cards and bids are never stuck
+    require!(
+        clock.unix_timestamp >= ctx.accounts.sealed_auction.reveal_end,
+        ErrorCode::SealedBidPhase
+    );
+    require!(!ctx.accounts.sealed_auction.is_settled, ErrorCode::AuctionNotActive);
+    require!(
+        ctx.accounts.sealed_auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    match (
+        ctx.accounts.sealed_auction.clearing_price(),
+        ctx.accounts.sealed_auction.highest_bidder,
+    ) {
+        (Some(price), Some(highest_bidder)) => {
+            require!(ctx.accounts.winner.key() == highest_bidder, ErrorCode::Unauthorized);
+            let winning_bid = ctx
+                .accounts
+                .winning_bid
+                .as_ref()
+                .ok_or(ErrorCode::InvalidReveal)?;
+            
+            let fee = ctx.accounts.marketplace.calculate_fee(price);
+            let royalties = creator_royalties(
+                &ctx.accounts.metadata,
+                ctx.accounts.card_mint.key(),
+                price,
+                ctx.remaining_accounts,
+            )?;
+            let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+            let seller_amount = price
+                .checked_sub(fee + royalty_total)
+                .ok_or(ErrorCode::InvalidPrice)?;
+            
+            // Pay from the winner's deposit; the bid account is program-owned, so lamports
+            // are moved directly and the rest of the deposit returns to the winner on close
+            winning_bid.sub_lamports(price)?;
+            ctx.accounts.seller.add_lamports(seller_amount)?;
+            ctx.accounts.treasury.add_lamports(fee)?;
+            
+            // Pay creator royalties
+            for royalty in &royalties {
+                require!(
+                    royalty.account.key() == royalty.creator,
+                    ErrorCode::InvalidCreatorAccount
+                );
+                royalty.account.add_lamports(royalty.amount)?;
+            }
+            
+            winning_bid.close(ctx.accounts.winner.to_account_info())?;
+            
+            // Transfer token from escrow to winner
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    to: ctx.accounts.winner_token_account.to_account_info(),
+                    authority: ctx.accounts.marketplace.to_account_info(),
+                },
+                marketplace_seeds,
+            );
+            
+            transfer(cpi_ctx, 1)?;
+            
+            // Update card ownership
+            let pookie_card = &mut ctx.accounts.pookie_card;
+            pookie_card.owner = highest_bidder;
+            pookie_card.last_updated = clock.unix_timestamp;
+            
+            // Update marketplace stats
+            let marketplace = &mut ctx.accounts.marketplace;
+            marketplace.total_volume += price;
+            marketplace.total_royalties += royalty_total;
+            marketplace.total_sales += 1;
+            
+            // Update user stats
+            let seller_stats = &mut ctx.accounts.seller_stats;
+            seller_stats.cards_owned -= 1;
+            seller_stats.total_earned += seller_amount;
+            seller_stats.last_activity = clock.unix_timestamp;
+            
+            let winner_stats = &mut ctx.accounts.winner_stats;
+            winner_stats.cards_owned += 1;
+            winner_stats.total_spent += price;
+            winner_stats.last_activity = clock.unix_timestamp;
+            
+            ctx.accounts.sealed_auction.open_bids -= 1;
+        }
+        _ => {
+            // No valid reveal, return to seller
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                Transfer {
+                    from: ctx.accounts.escrow_token_account.to_account_info(),
+                    to: ctx.accounts.seller_token_account.to_account_info(),
+                    authority: ctx.accounts.marketplace.to_account_info(),
+                },
+                marketplace_seeds,
+            );
+            
+            transfer(cpi_ctx, 1)?;
+        }
+    }
+    
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = false;
+    pookie_card.listing_price = None;
+    
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.is_settled = true;
+    
+    // Losing bids are claimed separately; close once none remain
+    if sealed_auction.open_bids == 0 {
+        sealed_auction.close(ctx.accounts.seller.to_account_info())?;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn claim_sealed_bid(ctx: Context<ClaimSealedBid>) -> Result<()> {
+    require!(ctx.accounts.sealed_auction.is_settled, ErrorCode::SealedBidPhase);
+    require!(
+        ctx.accounts.sealed_auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    // Unrevealed deposits are forfeited to the seller when the auction says so;
+    // everything else returns to the bidder when the bid account closes
+    if ctx.accounts.sealed_bid.revealed_amount.is_none() && ctx.accounts.sealed_auction.forfeit_unrevealed {
+        let deposit = ctx.accounts.sealed_bid.deposit;
+        ctx.accounts.sealed_bid.sub_lamports(deposit)?;
+        ctx.accounts.seller.add_lamports(deposit)?;
+    }
+    
+    let sealed_auction = &mut ctx.accounts.sealed_auction;
+    sealed_auction.open_bids -= 1;
+    
+    if sealed_auction.open_bids == 0 {
+        sealed_auction.close(ctx.accounts.seller.to_account_info())?;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn create_dutch_auction(
+    ctx: Context<CreateDutchAuction>,
+    start_price: u64,
+    floor_price: u64,
+    price_decrement: u64,
+    decay_interval: i64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(floor_price > 0, ErrorCode::InvalidPrice);
+    require!(start_price > floor_price, ErrorCode::InvalidPrice);
+    require!(price_decrement > 0, ErrorCode::InvalidPrice);
+    require!(decay_interval > 0, ErrorCode::InvalidPrice);
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    
+    // Transfer token to escrow
+    let cpi_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.seller_token_account.to_account_info(),
@@ -357,47 +3761,125 @@ pub fn list_card(ctx: Context<ListCard>, price: u64) -> Result<()> {
     
     transfer(cpi_ctx, 1)?;
     
-    // Create listing
-    let listing = &mut ctx.accounts.listing;
-    listing.seller = ctx.accounts.seller.key();
-    listing.mint = ctx.accounts.card_mint.key();
-    listing.price = price;
-    listing.created_at = clock.unix_timestamp;
-    listing.is_active = true;
+    // Create dutch auction
+    let dutch_auction = &mut ctx.accounts.dutch_auction;
+    dutch_auction.seller = ctx.accounts.seller.key();
+    dutch_auction.mint = ctx.accounts.card_mint.key();
+    dutch_auction.start_price = start_price;
+    dutch_auction.floor_price = floor_price;
+    dutch_auction.price_decrement = price_decrement;
+    dutch_auction.decay_interval = decay_interval;
+    dutch_auction.start_time = clock.unix_timestamp;
+    dutch_auction.created_at = clock.unix_timestamp;
+    dutch_auction.is_active = true;
     
     // Update card status
     let pookie_card = &mut ctx.accounts.pookie_card;
     pookie_card.is_listed = true;
-    pookie_card.listing_price = Some(price);
+    pookie_card.listing_price = Some(start_price);
     
     Ok(())
 }
 
-pub fn buy_card(ctx: Context<BuyCard>) -> Result<()> {
+pub(crate) fn buy_dutch_auction<'info>(ctx: Context<'_, '_, 'info, 'info, BuyDutchAuction<'info>>) -> Result<()> {
     let clock = Clock::get()?;
     
-    require!(ctx.accounts.listing.is_active, ErrorCode::NotListed);
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(ctx.accounts.dutch_auction.is_active, ErrorCode::AuctionNotActive);
     require!(
-        ctx.accounts.pookie_card.owner == ctx.accounts.listing.seller,
+        ctx.accounts.dutch_auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.dutch_auction.seller,
         ErrorCode::NotCardOwner
     );
     
-    let price = ctx.accounts.listing.price;
-    let fee = (price * ctx.accounts.marketplace.fee_percentage as u64) / 10000;
-    let seller_amount = price - fee;
+    let price = ctx.accounts.dutch_auction.current_price(clock.unix_timestamp);
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let royalties = creator_royalties(
+        &ctx.accounts.metadata,
+        ctx.accounts.card_mint.key(),
+        price,
+        ctx.remaining_accounts,
+    )?;
+    let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
     
-    // Transfer token from escrow to buyer
+    require!(ctx.accounts.buyer.lamports() >= price, ErrorCode::InsufficientFunds);
+    
+    // Pay creator royalties
+    for royalty in &royalties {
+        require!(
+            royalty.account.key() == royalty.creator,
+            ErrorCode::InvalidCreatorAccount
+        );
+        
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: royalty.account.clone(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, royalty.amount)?;
+    }
+    
+    // Pay the seller
     let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.buyer.to_account_info(),
+            to: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, seller_amount)?;
+    
+    // Route marketplace fee to treasury
+    if fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.buyer.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, fee)?;
+    }
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Transfer token from escrow to buyer
+    let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.escrow_token_account.to_account_info(),
             to: ctx.accounts.buyer_token_account.to_account_info(),
             authority: ctx.accounts.marketplace.to_account_info(),
         },
+        marketplace_seeds,
     );
     
     transfer(cpi_ctx, 1)?;
     
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
     // Update card ownership
     let pookie_card = &mut ctx.accounts.pookie_card;
     pookie_card.owner = ctx.accounts.buyer.key();
@@ -408,6 +3890,7 @@ pub fn buy_card(ctx: Context<BuyCard>) -> Result<()> {
     // Update marketplace stats
     let marketplace = &mut ctx.accounts.marketplace;
     marketplace.total_volume += price;
+    marketplace.total_royalties += royalty_total;
     marketplace.total_sales += 1;
     
     // Update user stats
@@ -424,25 +3907,41 @@ pub fn buy_card(ctx: Context<BuyCard>) -> Result<()> {
     Ok(())
 }
 
-pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
+pub(crate) fn cancel_dutch_auction(ctx: Context<CancelDutchAuction>) -> Result<()> {
     require!(
-        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
-        ErrorCode::NotCardOwner
+        ctx.accounts.dutch_auction.seller == ctx.accounts.seller.key(),
+        ErrorCode::Unauthorized
     );
-    require!(ctx.accounts.listing.is_active, ErrorCode::NotListed);
+    require!(ctx.accounts.dutch_auction.is_active, ErrorCode::AuctionNotActive);
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
     
     // Transfer token back to seller
-    let cpi_ctx = CpiContext::new(
+    let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.escrow_token_account.to_account_info(),
             to: ctx.accounts.seller_token_account.to_account_info(),
             authority: ctx.accounts.marketplace.to_account_info(),
         },
+        marketplace_seeds,
     );
     
     transfer(cpi_ctx, 1)?;
     
+    // Close escrow and return its rent to seller
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.seller.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
     // Update card status
     let pookie_card = &mut ctx.accounts.pookie_card;
     pookie_card.is_listed = false;
@@ -451,134 +3950,357 @@ pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
     Ok(())
 }
 
-pub fn create_auction(ctx: Context<CreateAuction>, starting_price: u64, duration: i64) -> Result<()> {
+pub(crate) fn make_offer(ctx: Context<MakeOffer>, amount: u64, expires_at: i64) -> Result<()> {
     let clock = Clock::get()?;
     
-    require!(starting_price > 0, ErrorCode::InvalidPrice);
-    require!(duration > 0, ErrorCode::InvalidPrice);
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(amount > 0, ErrorCode::InvalidPrice);
+    require!(expires_at > clock.unix_timestamp, ErrorCode::OfferExpired);
+    require!(
+        ctx.accounts.pookie_card.owner != ctx.accounts.buyer.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    // Escrow the offered amount in the offer account itself
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.buyer.to_account_info(),
+            to: ctx.accounts.offer.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, amount)?;
+    
+    let offer = &mut ctx.accounts.offer;
+    offer.buyer = ctx.accounts.buyer.key();
+    offer.mint = ctx.accounts.card_mint.key();
+    offer.amount = amount;
+    offer.expires_at = expires_at;
+    offer.created_at = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_offer(_ctx: Context<CancelOffer>) -> Result<()> {
+    // Closing the offer returns the escrowed amount and rent to the buyer
+    Ok(())
+}
+
+pub(crate) fn accept_offer<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
     require!(
         ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
         ErrorCode::NotCardOwner
     );
     require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    require!(clock.unix_timestamp < ctx.accounts.offer.expires_at, ErrorCode::OfferExpired);
     
-    // Transfer token to escrow
+    let price = ctx.accounts.offer.amount;
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let royalties = creator_royalties(
+        &ctx.accounts.metadata,
+        ctx.accounts.card_mint.key(),
+        price,
+        ctx.remaining_accounts,
+    )?;
+    let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    
+    // Release escrowed payment to seller, treasury and creators; the offer account is
+    // program-owned, so lamports are moved directly
+    ctx.accounts.offer.sub_lamports(price)?;
+    ctx.accounts.seller.add_lamports(seller_amount)?;
+    ctx.accounts.treasury.add_lamports(fee)?;
+    
+    // Pay creator royalties
+    for royalty in &royalties {
+        require!(
+            royalty.account.key() == royalty.creator,
+            ErrorCode::InvalidCreatorAccount
+        );
+        royalty.account.add_lamports(royalty.amount)?;
+    }
+    
+    // Transfer token to buyer
     let cpi_ctx = CpiContext::new(
         ctx.accounts.token_program.to_account_info(),
         Transfer {
             from: ctx.accounts.seller_token_account.to_account_info(),
-            to: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.buyer_token_account.to_account_info(),
             authority: ctx.accounts.seller.to_account_info(),
         },
     );
     
     transfer(cpi_ctx, 1)?;
     
-    // Create auction
-    let auction = &mut ctx.accounts.auction;
-    auction.seller = ctx.accounts.seller.key();
-    auction.mint = ctx.accounts.card_mint.key();
-    auction.starting_price = starting_price;
-    auction.current_bid = None;
-    auction.current_bidder = None;
-    auction.end_time = clock.unix_timestamp + duration;
-    auction.created_at = clock.unix_timestamp;
-    auction.is_active = true;
-    
-    // Update card status
+    // Update card ownership
     let pookie_card = &mut ctx.accounts.pookie_card;
-    pookie_card.is_listed = true;
-    pookie_card.listing_price = Some(starting_price);
+    pookie_card.owner = ctx.accounts.buyer.key();
+    pookie_card.last_updated = clock.unix_timestamp;
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += price;
+    marketplace.total_royalties += royalty_total;
+    marketplace.total_sales += 1;
+    
+    // Update user stats
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.cards_owned -= 1;
+    seller_stats.total_earned += seller_amount;
+    seller_stats.last_activity = clock.unix_timestamp;
+    
+    let buyer_stats = &mut ctx.accounts.buyer_stats;
+    buyer_stats.cards_owned += 1;
+    buyer_stats.total_spent += price;
+    buyer_stats.last_activity = clock.unix_timestamp;
     
     Ok(())
 }
 
-pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
+pub(crate) fn create_collection_bid(
+    ctx: Context<CreateCollectionBid>,
+    bid_id: u64,
+    price_per_card: u64,
+    quantity: u64,
+    rarity: Option<CardRarity>,
+    element: Option<CardElement>,
+) -> Result<()> {
     let clock = Clock::get()?;
     
-    require!(ctx.accounts.auction.is_active, ErrorCode::AuctionNotActive);
-    require!(clock.unix_timestamp < ctx.accounts.auction.end_time, ErrorCode::AuctionExpired);
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(price_per_card > 0, ErrorCode::InvalidPrice);
+    require!(quantity > 0, ErrorCode::InvalidPrice);
+    require!(ctx.accounts.collection.is_active, ErrorCode::CollectionNotFound);
     
-    let min_bid = match ctx.accounts.auction.current_bid {
-        Some(current_bid) => current_bid + (current_bid / 10), // 10% increment
-        None => ctx.accounts.auction.starting_price,
-    };
+    let total = price_per_card
+        .checked_mul(quantity)
+        .ok_or(ErrorCode::InvalidPrice)?;
     
-    require!(bid_amount >= min_bid, ErrorCode::BidTooLow);
+    // Escrow the full amount for every card wanted in the bid account itself
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.bidder.to_account_info(),
+            to: ctx.accounts.collection_bid.to_account_info(),
+        },
+    );
     
-    // Update auction
-    let auction = &mut ctx.accounts.auction;
-    auction.current_bid = Some(bid_amount);
-    auction.current_bidder = Some(ctx.accounts.bidder.key());
+    system_program::transfer(cpi_ctx, total)?;
+    
+    let collection_bid = &mut ctx.accounts.collection_bid;
+    collection_bid.bidder = ctx.accounts.bidder.key();
+    collection_bid.collection = ctx.accounts.collection.key();
+    collection_bid.bid_id = bid_id;
+    collection_bid.rarity = rarity;
+    collection_bid.element = element;
+    collection_bid.price_per_card = price_per_card;
+    collection_bid.quantity_remaining = quantity;
+    collection_bid.created_at = clock.unix_timestamp;
     
     Ok(())
 }
 
-pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
+pub(crate) fn cancel_collection_bid(_ctx: Context<CancelCollectionBid>) -> Result<()> {
+    // Closing the bid returns the unfilled escrow and rent to the bidder
+    Ok(())
+}
+
+pub(crate) fn fill_collection_bid<'info>(ctx: Context<'_, '_, 'info, 'info, FillCollectionBid<'info>>) -> Result<()> {
     let clock = Clock::get()?;
     
-    require!(clock.unix_timestamp >= ctx.accounts.auction.end_time, ErrorCode::AuctionExpired);
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.seller.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    require!(
+        ctx.accounts.collection_bid.quantity_remaining > 0,
+        ErrorCode::CollectionBidFilled
+    );
+    require!(
+        ctx.accounts.collection_bid.matches(&ctx.accounts.pookie_card),
+        ErrorCode::CardDoesNotMatchBid
+    );
     
-    if let Some(final_bid) = ctx.accounts.auction.current_bid {
-        if let Some(winner) = ctx.accounts.auction.current_bidder {
-            require!(winner == ctx.accounts.winner.key(), ErrorCode::Unauthorized);
-            
-            let fee = (final_bid * ctx.accounts.marketplace.fee_percentage as u64) / 10000;
-            let seller_amount = final_bid - fee;
-            
-            // Transfer token to winner
-            let cpi_ctx = CpiContext::new(
-                ctx.accounts.token_program.to_account_info(),
-                Transfer {
-                    from: ctx.accounts.escrow_token_account.to_account_info(),
-                    to: ctx.accounts.winner_token_account.to_account_info(),
-                    authority: ctx.accounts.marketplace.to_account_info(),
-                },
-            );
-            
-            transfer(cpi_ctx, 1)?;
-            
-            // Update card ownership
-            let pookie_card = &mut ctx.accounts.pookie_card;
-            pookie_card.owner = ctx.accounts.winner.key();
-            pookie_card.is_listed = false;
-            pookie_card.listing_price = None;
-            pookie_card.last_updated = clock.unix_timestamp;
-            
-            // Update marketplace stats
-            let marketplace = &mut ctx.accounts.marketplace;
-            marketplace.total_volume += final_bid;
-            marketplace.total_sales += 1;
-            
-            // Update user stats
-            let seller_stats = &mut ctx.accounts.seller_stats;
-            seller_stats.cards_owned -= 1;
-            seller_stats.total_earned += seller_amount;
-            seller_stats.last_activity = clock.unix_timestamp;
-            
-            let winner_stats = &mut ctx.accounts.winner_stats;
-            winner_stats.cards_owned += 1;
-            winner_stats.total_spent += final_bid;
-            winner_stats.last_activity = clock.unix_timestamp;
-        }
-    } else {
-        // No bids, return to seller
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
-                from: ctx.accounts.escrow_token_account.to_account_info(),
-                to: ctx.accounts.seller_token_account.to_account_info(),
-                authority: ctx.accounts.marketplace.to_account_info(),
-            },
+    let price = ctx.accounts.collection_bid.price_per_card;
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let royalties = creator_royalties(
+        &ctx.accounts.metadata,
+        ctx.accounts.card_mint.key(),
+        price,
+        ctx.remaining_accounts,
+    )?;
+    let royalty_total: u64 = royalties.iter().map(|royalty| royalty.amount).sum();
+    let seller_amount = price
+        .checked_sub(fee + royalty_total)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    
+    // Release one card's worth of escrow to seller, treasury and creators
+    ctx.accounts.collection_bid.sub_lamports(price)?;
+    ctx.accounts.seller.add_lamports(seller_amount)?;
+    ctx.accounts.treasury.add_lamports(fee)?;
+    
+    // Pay creator royalties
+    for royalty in &royalties {
+        require!(
+            royalty.account.key() == royalty.creator,
+            ErrorCode::InvalidCreatorAccount
         );
-        
-        transfer(cpi_ctx, 1)?;
-        
-        // Update card status
-        let pookie_card = &mut ctx.accounts.pookie_card;
-        pookie_card.is_listed = false;
-        pookie_card.listing_price = None;
+        royalty.account.add_lamports(royalty.amount)?;
+    }
+    
+    // Transfer token to bidder
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.seller_token_account.to_account_info(),
+            to: ctx.accounts.bidder_token_account.to_account_info(),
+            authority: ctx.accounts.seller.to_account_info(),
+        },
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Update card ownership
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.owner = ctx.accounts.bidder.key();
+    pookie_card.last_updated = clock.unix_timestamp;
+    
+    // Update marketplace stats
+    let marketplace = &mut ctx.accounts.marketplace;
+    marketplace.total_volume += price;
+    marketplace.total_royalties += royalty_total;
+    marketplace.total_sales += 1;
+    
+    // Update user stats
+    let seller_stats = &mut ctx.accounts.seller_stats;
+    seller_stats.cards_owned -= 1;
+    seller_stats.total_earned += seller_amount;
+    seller_stats.last_activity = clock.unix_timestamp;
+    
+    let bidder_stats = &mut ctx.accounts.bidder_stats;
+    bidder_stats.cards_owned += 1;
+    bidder_stats.total_spent += price;
+    bidder_stats.last_activity = clock.unix_timestamp;
+    
+    // Decrement the bid, closing it once fully filled
+    let collection_bid = &mut ctx.accounts.collection_bid;
+    collection_bid.quantity_remaining -= 1;
+    if collection_bid.quantity_remaining == 0 {
+        collection_bid.close(ctx.accounts.bidder.to_account_info())?;
     }
     
     Ok(())
 }
+
+struct CreatorRoyalty<'a, 'info> {
+    creator: Pubkey,
+    account: &'a AccountInfo<'info>,
+    amount: u64,
+}
+
+/// Splits the creator royalty on `price` between the card's metadata creators.
+/// `creator_accounts` are the remaining accounts, one per creator in metadata order.
+fn creator_royalties<'a, 'info>(
+    metadata: &MetadataAccount,
+    card_mint: Pubkey,
+    price: u64,
+    creator_accounts: &'a [AccountInfo<'info>],
+) -> Result<Vec<CreatorRoyalty<'a, 'info>>> {
+    require!(metadata.mint == card_mint, ErrorCode::InvalidMetadata);
+    
+    let creators = match &metadata.creators {
+        Some(creators) => creators,
+        None => return Ok(Vec::new()),
+    };
+    require!(
+        creator_accounts.len() >= creators.len(),
+        ErrorCode::InvalidCreatorAccount
+    );
+    
+    let royalty = (price as u128 * metadata.seller_fee_basis_points as u128) / 10000;
+    Ok(creators
+        .iter()
+        .zip(creator_accounts)
+        .map(|(creator, account)| CreatorRoyalty {
+            creator: creator.address,
+            account,
+            amount: ((royalty * creator.share as u128) / 100) as u64,
+        })
+        .filter(|royalty| royalty.amount > 0)
+        .collect())
+}
+
+/// Checks that a listing can still be bought by `buyer` in a sweep.
+/// Sweeps settle in SOL only, so token-priced listings are treated as unavailable.
+fn check_sweep_listing(listing: &Listing, pookie_card: &PookieCard, buyer: Pubkey, now: i64) -> Result<()> {
+    require!(listing.is_active, ErrorCode::NotListed);
+    require!(!listing.is_expired(now), ErrorCode::ListingExpired);
+    require!(
+        listing.reserved_buyer.is_none_or(|reserved_buyer| reserved_buyer == buyer),
+        ErrorCode::ListingReserved
+    );
+    require!(listing.payment_mint.is_none(), ErrorCode::InvalidPaymentAccount);
+    require!(listing.seller != buyer, ErrorCode::Unauthorized);
+    require!(pookie_card.owner == listing.seller, ErrorCode::NotCardOwner);
+    Ok(())
+}
+
+/// Returns the payment token account if it holds `payment_mint` and belongs to `owner`.
+fn payment_account<'a, 'info>(
+    account: &'a Option<Box<Account<'info, TokenAccount>>>,
+    payment_mint: Pubkey,
+    owner: Pubkey,
+) -> Result<&'a Account<'info, TokenAccount>> {
+    let account = account.as_deref().ok_or(ErrorCode::InvalidPaymentAccount)?;
+    require!(
+        account.mint == payment_mint && account.owner == owner,
+        ErrorCode::InvalidPaymentAccount
+    );
+    Ok(account)
+}
+
+/// Refund accounts must be the owner's associated token account, so a closed one can be recreated
+fn associated_payment_account<'a, 'info>(
+    account: &'a Option<UncheckedAccount<'info>>,
+    mint: &'a Option<UncheckedAccount<'info>>,
+    payment_mint: Pubkey,
+    owner: Pubkey,
+) -> Result<(&'a AccountInfo<'info>, &'a AccountInfo<'info>)> {
+    let account = account.as_deref().ok_or(ErrorCode::InvalidPaymentAccount)?;
+    let mint = mint.as_deref().ok_or(ErrorCode::InvalidPaymentAccount)?;
+    require!(
+        mint.key() == payment_mint
+            && account.key() == get_associated_token_address(&owner, &payment_mint),
+        ErrorCode::InvalidPaymentAccount
+    );
+    Ok((account, mint))
+}
+
+fn transfer_payment_tokens<'info>(
+    token_program: &Program<'info, Token>,
+    from: &Account<'info, TokenAccount>,
+    to: &Account<'info, TokenAccount>,
+    authority: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let cpi_ctx = CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        Transfer {
+            from: from.to_account_info(),
+            to: to.to_account_info(),
+            authority,
+        },
+        signer_seeds,
+    );
+    
+    transfer(cpi_ctx, amount)
+}
@@ -1,40 +1,67 @@
 use anchor_lang::prelude::*;
+use anchor_lang::system_program;
 use anchor_spl::token::{Mint, Token, TokenAccount, mint_to, MintTo};
-use anchor_spl::metadata::{Metadata, MetadataAccount, MasterEdition, MasterEditionAccount, CreateMasterEdition, CreateMetadataAccountsV3, CreateMetadataAccountsV3InstructionArgs};
+use anchor_spl::metadata::{
+    create_master_edition_v3, create_metadata_accounts_v3, verify_sized_collection_item, CreateMasterEditionV3,
+    CreateMetadataAccountsV3, Metadata, VerifySizedCollectionItem,
+};
+use anchor_spl::metadata::mpl_token_metadata::types::{Collection, Creator, DataV2};
 use anchor_spl::associated_token::AssociatedToken;
+use solana_sha256_hasher::hashv;
 
 use crate::state::*;
 use crate::error::ErrorCode;
 
 #[derive(Accounts)]
-#[instruction(card_id: u64, name: String, rarity: CardRarity, element: CardElement)]
+#[instruction(card_id: u64, name: String, rarity: Option<CardRarity>, element: Option<CardElement>)]
 pub struct MintCard<'info> {
+    /// Pays for the card's accounts; anyone may complete a pending request
     #[account(mut)]
     pub payer: Signer<'info>,
     
+    /// Receives the card: the requester, or the payer when minting without a request
+    pub owner: SystemAccount<'info>,
+    
+    /// CHECK: Program PDA holding mint and freeze authority; the master edition takes both over at mint
+    #[account(
+        seeds = [b"mint_authority"],
+        bump
+    )]
+    pub mint_authority: UncheckedAccount<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        mint::decimals = 0,
+        mint::authority = mint_authority,
+        mint::freeze_authority = mint_authority,
+    )]
+    pub mint: Account<'info, Mint>,
+    
     #[account(
         init,
         payer = payer,
         space = 8 + PookieCard::INIT_SPACE,
-        seeds = [b"pookie_card", card_id.to_le_bytes().as_ref()],
+        seeds = [b"pookie_card", mint.key().as_ref()],
         bump
     )]
     pub pookie_card: Account<'info, PookieCard>,
     
+    /// Reserved by the mint request, if any
     #[account(
-        init,
+        init_if_needed,
         payer = payer,
-        mint::decimals = 0,
-        mint::authority = payer,
-        mint::freeze_authority = payer,
+        space = 8 + CardIndex::INIT_SPACE,
+        seeds = [b"card_index", card_id.to_le_bytes().as_ref()],
+        bump
     )]
-    pub mint: Account<'info, Mint>,
+    pub card_index: Account<'info, CardIndex>,
     
     #[account(
         init_if_needed,
         payer = payer,
         associated_token::mint = mint,
-        associated_token::authority = payer,
+        associated_token::authority = owner,
     )]
     pub token_account: Account<'info, TokenAccount>,
     
@@ -48,7 +75,7 @@ pub struct MintCard<'info> {
     
     #[account(
         mut,
-        seeds = [b"user_stats", payer.key().as_ref()],
+        seeds = [b"user_stats", owner.key().as_ref()],
         bump
     )]
     pub user_stats: Account<'info, UserStats>,
@@ -63,6 +90,28 @@ pub struct MintCard<'info> {
     /// CHECK: This is the collection authority
     pub collection_authority: UncheckedAccount<'info>,
     
+    pub collection_mint: Box<Account<'info, Mint>>,
+    
+    /// CHECK: Collection NFT metadata, checked by the token metadata program
+    #[account(mut)]
+    pub collection_metadata: UncheckedAccount<'info>,
+    
+    /// CHECK: Collection NFT master edition, checked by the token metadata program
+    pub collection_master_edition: UncheckedAccount<'info>,
+    
+    /// Earlier commitment to mint, required unless the minter chooses both rarity and element
+    #[account(
+        mut,
+        close = payer,
+        seeds = [b"mint_request", owner.key().as_ref()],
+        bump
+    )]
+    pub mint_request: Option<Account<'info, MintRequest>>,
+    
+    /// CHECK: SlotHashes sysvar, read as the randomness source for rarity and element
+    #[account(address = anchor_lang::solana_program::sysvar::slot_hashes::ID)]
+    pub recent_slothashes: UncheckedAccount<'info>,
+    
     pub token_program: Program<'info, Token>,
     pub token_metadata_program: Program<'info, Metadata>,
     pub associated_token_program: Program<'info, AssociatedToken>,
@@ -73,24 +122,113 @@ pub struct MintCard<'info> {
 impl<'info> MintCard<'info> {
     pub fn validate(
         ctx: &Context<MintCard>,
-        rarity: &CardRarity,
-        element: &CardElement,
+        card_id: u64,
+        name: &str,
+        rarity: &Option<CardRarity>,
+        element: &Option<CardElement>,
     ) -> Result<()> {
-        // Validate collection is active
-        require!(ctx.accounts.collection.is_active, ErrorCode::CollectionNotFound);
+        require!(
+            ctx.accounts.collection_mint.key() == ctx.accounts.collection.collection_mint,
+            ErrorCode::CollectionNotFound
+        );
         
-        // Check max supply if set
-        if let Some(max_supply) = ctx.accounts.collection.max_supply {
+        // Only the collection's minter may choose rarity or element
+        if rarity.is_some() || element.is_some() {
             require!(
-                ctx.accounts.collection.total_cards < max_supply,
-                ErrorCode::MaxSupplyReached
+                ctx.accounts.collection.minter == Some(ctx.accounts.payer.key()),
+                ErrorCode::Unauthorized
             );
         }
         
+        match &ctx.accounts.mint_request {
+            // The request fixed the card, its owner and its supply slot, so it is
+            // completed exactly as committed
+            Some(mint_request) => {
+                require!(
+                    mint_request.collection == ctx.accounts.collection.key()
+                        && mint_request.card_id == card_id
+                        && mint_request.name == name
+                        && ctx.accounts.card_index.mint == mint_request.key(),
+                    ErrorCode::InvalidMintRequest
+                );
+                // A minter may only choose traits on its own requests
+                if rarity.is_some() || element.is_some() {
+                    require!(
+                        ctx.accounts.owner.key() == ctx.accounts.payer.key(),
+                        ErrorCode::Unauthorized
+                    );
+                }
+            }
+            // Anything rolled must come from a request committed in an earlier slot
+            None => {
+                require!(rarity.is_some() && element.is_some(), ErrorCode::InvalidMintRequest);
+                require!(
+                    ctx.accounts.owner.key() == ctx.accounts.payer.key(),
+                    ErrorCode::Unauthorized
+                );
+                require!(
+                    ctx.accounts.card_index.mint == Pubkey::default(),
+                    ErrorCode::InvalidMintRequest
+                );
+                require!(ctx.accounts.collection.is_active, ErrorCode::CollectionNotFound);
+                if let Some(max_supply) = ctx.accounts.collection.max_supply {
+                    require!(
+                        ctx.accounts.collection.total_cards < max_supply,
+                        ErrorCode::MaxSupplyReached
+                    );
+                }
+            }
+        }
+        
         Ok(())
     }
 }
 
+#[derive(Accounts)]
+#[instruction(card_id: u64)]
+pub struct RequestMint<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + MintRequest::INIT_SPACE,
+        seeds = [b"mint_request", payer.key().as_ref()],
+        bump
+    )]
+    pub mint_request: Account<'info, MintRequest>,
+    
+    /// Reserves the card id until the request is completed
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + CardIndex::INIT_SPACE,
+        seeds = [b"card_index", card_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub card_index: Account<'info, CardIndex>,
+    
+    /// Must exist so that anyone can complete the mint
+    #[account(
+        seeds = [b"user_stats", payer.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"collection", collection_authority.key().as_ref()],
+        bump
+    )]
+    pub collection: Account<'info, CardCollection>,
+    
+    /// CHECK: This is the collection authority
+    pub collection_authority: UncheckedAccount<'info>,
+    
+    pub system_program: Program<'info, System>,
+}
+
 #[derive(Accounts)]
 pub struct InitializeUserStats<'info> {
     #[account(
@@ -108,88 +246,141 @@ pub struct InitializeUserStats<'info> {
     pub system_program: Program<'info, System>,
 }
 
-pub fn handler(ctx: Context<MintCard>, card_id: u64, name: String, rarity: CardRarity, element: CardElement) -> Result<()> {
+pub(crate) fn handler(
+    ctx: Context<MintCard>,
+    card_id: u64,
+    name: String,
+    rarity: Option<CardRarity>,
+    element: Option<CardElement>,
+) -> Result<()> {
     let clock = Clock::get()?;
     
     // Validate inputs
-    MintCard::validate(&ctx, &rarity, &element)?;
+    MintCard::validate(&ctx, card_id, &name, &rarity, &element)?;
+    
+    // Roll rarity and element unless the minter chose them
+    let seed = match &ctx.accounts.mint_request {
+        Some(mint_request) => randomness_seed(
+            &ctx.accounts.recent_slothashes,
+            mint_request.slot,
+            &[mint_request.payer.as_ref(), &mint_request.slot.to_le_bytes()],
+        )?,
+        None => [0; 32],
+    };
+    let rarity = rarity.unwrap_or_else(|| {
+        ctx.accounts
+            .collection
+            .roll_rarity(u64::from_le_bytes(seed[0..8].try_into().unwrap()))
+    });
+    let element = element.unwrap_or_else(|| {
+        let roll = u64::from_le_bytes(seed[8..16].try_into().unwrap());
+        CardElement::ALL[(roll % CardElement::ALL.len() as u64) as usize].clone()
+    });
     
     // Get base stats from rarity
     let (base_attack, base_defense, base_health) = rarity.base_stats();
     
     // Create metadata
-    let metadata_data = CreateMetadataAccountsV3InstructionArgs {
-        data: anchor_spl::metadata::MetadataV3 {
-            name: name.clone(),
-            symbol: "POOKIE".to_string(),
-            uri: format!("https://api.pookiecards.com/metadata/{}", card_id),
-            seller_fee_basis_points: 500, // 5%
-            creators: Some(vec![
-                anchor_spl::metadata::Creator {
-                    address: ctx.accounts.collection_authority.key(),
-                    verified: true,
-                    share: 100,
-                },
-            ]),
-            collection: Some(anchor_spl::metadata::Collection {
-                key: ctx.accounts.collection.key(),
+    let metadata_data = DataV2 {
+        name: name.clone(),
+        symbol: ctx.accounts.collection.symbol.clone(),
+        uri: ctx.accounts.collection.metadata_uri(card_id),
+        seller_fee_basis_points: ctx.accounts.collection.seller_fee_basis_points,
+        // The collection authority does not sign the mint, so it cannot be a verified creator
+        creators: Some(vec![
+            Creator {
+                address: ctx.accounts.collection_authority.key(),
                 verified: false,
-            }),
-            uses: None,
-        },
-        is_mutable: true,
-        collection_details: None,
+                share: 100,
+            },
+        ]),
+        // Verified below via the collection PDA
+        collection: Some(Collection {
+            key: ctx.accounts.collection.collection_mint,
+            verified: false,
+        }),
+        uses: None,
     };
     
-    let cpi_ctx = CpiContext::new(
+    let mint_authority_seeds: &[&[&[u8]]] = &[&[b"mint_authority", &[ctx.bumps.mint_authority]]];
+    let collection_authority_key = ctx.accounts.collection_authority.key();
+    let collection_seeds: &[&[&[u8]]] = &[&[
+        b"collection",
+        collection_authority_key.as_ref(),
+        &[ctx.bumps.collection],
+    ]];
+    
+    // The collection PDA is the card's update authority, so minters cannot rewrite its uri, fees or creators
+    let metadata_signer_seeds: &[&[&[u8]]] = &[mint_authority_seeds[0], collection_seeds[0]];
+    
+    let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_metadata_program.to_account_info(),
         CreateMetadataAccountsV3 {
             metadata: ctx.accounts.metadata.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-            mint_authority: ctx.accounts.payer.to_account_info(),
-            update_authority: ctx.accounts.payer.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
             payer: ctx.accounts.payer.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
             rent: ctx.accounts.rent.to_account_info(),
         },
+        metadata_signer_seeds,
     );
     
-    anchor_spl::metadata::create_metadata_accounts_v3(cpi_ctx, metadata_data)?;
+    create_metadata_accounts_v3(cpi_ctx, metadata_data, true, true, None)?;
     
-    // Create master edition
-    let cpi_ctx = CpiContext::new(
+    // Mint token to user
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        MintTo {
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.token_account.to_account_info(),
+            authority: ctx.accounts.mint_authority.to_account_info(),
+        },
+        mint_authority_seeds,
+    );
+    
+    mint_to(cpi_ctx, 1)?;
+    
+    // Create master edition, which takes over mint and freeze authority and fixes supply at one
+    let cpi_ctx = CpiContext::new_with_signer(
         ctx.accounts.token_metadata_program.to_account_info(),
-        CreateMasterEdition {
+        CreateMasterEditionV3 {
             edition: ctx.accounts.master_edition.to_account_info(),
             mint: ctx.accounts.mint.to_account_info(),
-            update_authority: ctx.accounts.payer.to_account_info(),
-            mint_authority: ctx.accounts.payer.to_account_info(),
+            update_authority: ctx.accounts.collection.to_account_info(),
+            mint_authority: ctx.accounts.mint_authority.to_account_info(),
             payer: ctx.accounts.payer.to_account_info(),
             metadata: ctx.accounts.metadata.to_account_info(),
             token_program: ctx.accounts.token_program.to_account_info(),
             system_program: ctx.accounts.system_program.to_account_info(),
             rent: ctx.accounts.rent.to_account_info(),
         },
+        metadata_signer_seeds,
     );
     
-    create_master_edition(cpi_ctx, Some(0))?;
+    create_master_edition_v3(cpi_ctx, Some(0))?;
     
-    // Mint token to user
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        MintTo {
-            mint: ctx.accounts.mint.to_account_info(),
-            to: ctx.accounts.token_account.to_account_info(),
-            authority: ctx.accounts.payer.to_account_info(),
+    // Verify the card into the collection NFT, signed by the collection PDA
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_metadata_program.to_account_info(),
+        VerifySizedCollectionItem {
+            payer: ctx.accounts.payer.to_account_info(),
+            metadata: ctx.accounts.metadata.to_account_info(),
+            collection_authority: ctx.accounts.collection.to_account_info(),
+            collection_mint: ctx.accounts.collection_mint.to_account_info(),
+            collection_metadata: ctx.accounts.collection_metadata.to_account_info(),
+            collection_master_edition: ctx.accounts.collection_master_edition.to_account_info(),
         },
+        collection_seeds,
     );
     
-    mint_to(cpi_ctx, 1)?;
+    verify_sized_collection_item(cpi_ctx, None)?;
     
     // Initialize PookieCard
     let pookie_card = &mut ctx.accounts.pookie_card;
     pookie_card.mint = ctx.accounts.mint.key();
-    pookie_card.owner = ctx.accounts.payer.key();
+    pookie_card.owner = ctx.accounts.owner.key();
     pookie_card.card_id = card_id;
     pookie_card.name = name;
     pookie_card.rarity = rarity;
@@ -204,6 +395,12 @@ pub fn handler(ctx: Context<MintCard>, card_id: u64, name: String, rarity: CardR
     pookie_card.last_updated = clock.unix_timestamp;
     pookie_card.is_listed = false;
     pookie_card.listing_price = None;
+    pookie_card.collection = ctx.accounts.collection.key();
+    
+    // Index the card id to its mint
+    let card_index = &mut ctx.accounts.card_index;
+    card_index.card_id = card_id;
+    card_index.mint = ctx.accounts.mint.key();
     
     // Update user stats
     let user_stats = &mut ctx.accounts.user_stats;
@@ -211,14 +408,58 @@ pub fn handler(ctx: Context<MintCard>, card_id: u64, name: String, rarity: CardR
     user_stats.cards_minted += 1;
     user_stats.last_activity = clock.unix_timestamp;
     
-    // Update collection stats
+    // Requests counted the card when they reserved it
+    if ctx.accounts.mint_request.is_none() {
+        let collection = &mut ctx.accounts.collection;
+        collection.total_cards += 1;
+    }
+    
+    Ok(())
+}
+
+pub(crate) fn request_mint(ctx: Context<RequestMint>, card_id: u64, name: String) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(ctx.accounts.collection.is_active, ErrorCode::CollectionNotFound);
+    require!(
+        !name.is_empty() && name.len() <= MintRequest::MAX_NAME_LEN,
+        ErrorCode::InvalidMintRequest
+    );
+    
+    // Reserve the supply slot now, so the request can always be completed
     let collection = &mut ctx.accounts.collection;
+    if let Some(max_supply) = collection.max_supply {
+        require!(collection.total_cards < max_supply, ErrorCode::MaxSupplyReached);
+    }
     collection.total_cards += 1;
     
+    // Hold the deposit in the request; it only leaves when the card is minted
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.payer.to_account_info(),
+            to: ctx.accounts.mint_request.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, MintRequest::CRANK_DEPOSIT)?;
+    
+    let card_index = &mut ctx.accounts.card_index;
+    card_index.card_id = card_id;
+    card_index.mint = ctx.accounts.mint_request.key();
+    
+    let mint_request = &mut ctx.accounts.mint_request;
+    mint_request.payer = ctx.accounts.payer.key();
+    mint_request.collection = ctx.accounts.collection.key();
+    mint_request.card_id = card_id;
+    mint_request.name = name;
+    mint_request.slot = clock.slot;
+    mint_request.created_at = clock.unix_timestamp;
+    
     Ok(())
 }
 
-pub fn initialize_user_stats(ctx: Context<InitializeUserStats>) -> Result<()> {
+pub(crate) fn initialize_user_stats(ctx: Context<InitializeUserStats>) -> Result<()> {
     let clock = Clock::get()?;
     
     let user_stats = &mut ctx.accounts.user_stats;
@@ -236,3 +477,62 @@ pub fn initialize_user_stats(ctx: Context<InitializeUserStats>) -> Result<()> {
     
     Ok(())
 }
+
+fn randomness_seed(recent_slothashes: &AccountInfo, request_slot: u64, entropy: &[&[u8]]) -> Result<[u8; 32]> {
+    slot_hash_seed(&recent_slothashes.try_borrow_data()?, request_slot, entropy)
+}
+
+fn slot_hash_seed(data: &[u8], request_slot: u64, entropy: &[&[u8]]) -> Result<[u8; 32]> {
+    // SlotHashes layout: u64 entry count, then (slot, hash) pairs newest first
+    require!(data.len() >= 8, ErrorCode::InvalidRarity);
+    let count = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
+    require!(data.len() >= 8 + count * 40, ErrorCode::InvalidRarity);
+    
+    // Find the first slot after the request, whose hash was unknown when it was committed
+    let entry_slot = |index: usize| u64::from_le_bytes(data[8 + index * 40..16 + index * 40].try_into().unwrap());
+    let Some(boundary) = (0..count).find(|index| entry_slot(*index) <= request_slot) else {
+        // That hash has aged out of the sysvar; lapsed requests roll the lowest outcome
+        // so letting a bad roll expire is never worth it
+        return Ok([0; 32]);
+    };
+    require!(boundary > 0, ErrorCode::MintRequestPending);
+    
+    let hash_start = 16 + (boundary - 1) * 40;
+    let mut inputs: Vec<&[u8]> = vec![&data[hash_start..hash_start + 32]];
+    inputs.extend_from_slice(entropy);
+    
+    Ok(hashv(&inputs).to_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn slot_hashes(entries: &[(u64, [u8; 32])]) -> Vec<u8> {
+        let mut data = (entries.len() as u64).to_le_bytes().to_vec();
+        for (slot, hash) in entries {
+            data.extend_from_slice(&slot.to_le_bytes());
+            data.extend_from_slice(hash);
+        }
+        data
+    }
+    
+    #[test]
+    fn rolls_from_first_hash_after_request() {
+        let data = slot_hashes(&[(12, [3; 32]), (11, [2; 32]), (10, [1; 32])]);
+        let seed = slot_hash_seed(&data, 10, &[b"entropy"]).unwrap();
+        assert_eq!(seed, hashv(&[&[2; 32], b"entropy"]).to_bytes());
+    }
+    
+    #[test]
+    fn pending_until_a_later_slot_is_hashed() {
+        let data = slot_hashes(&[(12, [3; 32]), (11, [2; 32])]);
+        assert!(slot_hash_seed(&data, 12, &[]).is_err());
+    }
+    
+    #[test]
+    fn aged_out_request_rolls_lowest() {
+        let data = slot_hashes(&[(700, [3; 32]), (699, [2; 32])]);
+        assert_eq!(slot_hash_seed(&data, 100, &[b"entropy"]).unwrap(), [0; 32]);
+    }
+}
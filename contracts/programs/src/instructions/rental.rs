@@ -0,0 +1,447 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, Transfer, transfer};
+use anchor_spl::associated_token::AssociatedToken;
+
+use crate::state::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+pub struct ListCardForRent<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RentalListing::INIT_SPACE,
+        seeds = [b"rental", card_mint.key().as_ref()],
+        bump
+    )]
+    pub rental_listing: Account<'info, RentalListing>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RentCard<'info> {
+    #[account(mut)]
+    pub renter: Signer<'info>,
+    
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"rental", card_mint.key().as_ref()],
+        bump
+    )]
+    pub rental_listing: Account<'info, RentalListing>,
+    
+    #[account(
+        init,
+        payer = renter,
+        space = 8 + UsageRights::INIT_SPACE,
+        seeds = [b"usage_rights", card_mint.key().as_ref()],
+        bump
+    )]
+    pub usage_rights: Account<'info, UsageRights>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"treasury"],
+        bump
+    )]
+    pub treasury: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", owner.key().as_ref()],
+        bump
+    )]
+    pub owner_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", renter.key().as_ref()],
+        bump
+    )]
+    pub renter_stats: Account<'info, UserStats>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReturnRentedCard<'info> {
+    /// Anyone may crank an expired rental
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    
+    #[account(mut)]
+    pub owner: SystemAccount<'info>,
+    
+    #[account(mut)]
+    pub renter: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"rental", card_mint.key().as_ref()],
+        bump
+    )]
+    pub rental_listing: Account<'info, RentalListing>,
+    
+    #[account(
+        mut,
+        close = renter,
+        seeds = [b"usage_rights", card_mint.key().as_ref()],
+        bump
+    )]
+    pub usage_rights: Account<'info, UsageRights>,
+    
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = card_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRentalListing<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+    
+    #[account(
+        mut,
+        seeds = [b"pookie_card", card_mint.key().as_ref()],
+        bump
+    )]
+    pub pookie_card: Account<'info, PookieCard>,
+    
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"rental", card_mint.key().as_ref()],
+        bump
+    )]
+    pub rental_listing: Account<'info, RentalListing>,
+    
+    #[account(
+        mut,
+        associated_token::mint = card_mint,
+        associated_token::authority = owner,
+    )]
+    pub owner_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        mut,
+        seeds = [b"escrow", card_mint.key().as_ref()],
+        bump,
+        token::mint = card_mint,
+        token::authority = marketplace,
+    )]
+    pub escrow_token_account: Account<'info, TokenAccount>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub card_mint: Box<Account<'info, anchor_spl::token::Mint>>,
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn list_card_for_rent(
+    ctx: Context<ListCardForRent>,
+    price_per_period: u64,
+    period_duration: i64,
+    max_periods: u32,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(price_per_period > 0, ErrorCode::InvalidPrice);
+    require!(
+        period_duration > 0 && period_duration <= RentalListing::MAX_PERIOD_DURATION && max_periods > 0,
+        ErrorCode::InvalidRentalTerms
+    );
+    require!(
+        ctx.accounts.pookie_card.owner == ctx.accounts.owner.key(),
+        ErrorCode::NotCardOwner
+    );
+    require!(!ctx.accounts.pookie_card.is_listed, ErrorCode::AlreadyListed);
+    
+    // Transfer token to escrow
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.owner_token_account.to_account_info(),
+            to: ctx.accounts.escrow_token_account.to_account_info(),
+            authority: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Create rental listing
+    let rental_listing = &mut ctx.accounts.rental_listing;
+    rental_listing.owner = ctx.accounts.owner.key();
+    rental_listing.mint = ctx.accounts.card_mint.key();
+    rental_listing.price_per_period = price_per_period;
+    rental_listing.period_duration = period_duration;
+    rental_listing.max_periods = max_periods;
+    rental_listing.renter = None;
+    rental_listing.created_at = clock.unix_timestamp;
+    
+    // Escrowed cards cannot be listed, sold or transferred elsewhere
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = true;
+    
+    Ok(())
+}
+
+pub(crate) fn rent_card(ctx: Context<RentCard>, periods: u32) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(ctx.accounts.rental_listing.renter.is_none(), ErrorCode::RentalActive);
+    require!(
+        periods > 0 && periods <= ctx.accounts.rental_listing.max_periods,
+        ErrorCode::InvalidRentalTerms
+    );
+    require!(
+        ctx.accounts.rental_listing.owner == ctx.accounts.owner.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.renter.key() != ctx.accounts.owner.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let price = ctx
+        .accounts
+        .rental_listing
+        .price_per_period
+        .checked_mul(periods as u64)
+        .ok_or(ErrorCode::InvalidPrice)?;
+    let fee = ctx.accounts.marketplace.calculate_fee(price);
+    let owner_amount = price - fee;
+    let expires_at = ctx
+        .accounts
+        .rental_listing
+        .period_duration
+        .checked_mul(periods as i64)
+        .and_then(|duration| clock.unix_timestamp.checked_add(duration))
+        .ok_or(ErrorCode::InvalidRentalTerms)?;
+    
+    require!(ctx.accounts.renter.lamports() >= price, ErrorCode::InsufficientFunds);
+    
+    // Pay the owner
+    let cpi_ctx = CpiContext::new(
+        ctx.accounts.system_program.to_account_info(),
+        system_program::Transfer {
+            from: ctx.accounts.renter.to_account_info(),
+            to: ctx.accounts.owner.to_account_info(),
+        },
+    );
+    
+    system_program::transfer(cpi_ctx, owner_amount)?;
+    
+    // Route marketplace fee to treasury
+    if fee > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.renter.to_account_info(),
+                to: ctx.accounts.treasury.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, fee)?;
+    }
+    
+    // Grant usage rights until expiry
+    let usage_rights = &mut ctx.accounts.usage_rights;
+    usage_rights.renter = ctx.accounts.renter.key();
+    usage_rights.owner = ctx.accounts.owner.key();
+    usage_rights.mint = ctx.accounts.card_mint.key();
+    usage_rights.expires_at = expires_at;
+    usage_rights.created_at = clock.unix_timestamp;
+    
+    let rental_listing = &mut ctx.accounts.rental_listing;
+    rental_listing.renter = Some(ctx.accounts.renter.key());
+    
+    // Update user stats
+    let owner_stats = &mut ctx.accounts.owner_stats;
+    owner_stats.total_earned += owner_amount;
+    owner_stats.last_activity = clock.unix_timestamp;
+    
+    let renter_stats = &mut ctx.accounts.renter_stats;
+    renter_stats.total_spent += price;
+    renter_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn return_rented_card(ctx: Context<ReturnRentedCard>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(
+        !ctx.accounts.usage_rights.is_active(clock.unix_timestamp),
+        ErrorCode::RentalActive
+    );
+    require!(
+        ctx.accounts.rental_listing.owner == ctx.accounts.owner.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(
+        ctx.accounts.usage_rights.renter == ctx.accounts.renter.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Transfer token back to owner
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to owner
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = false;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_rental_listing(ctx: Context<CancelRentalListing>) -> Result<()> {
+    require!(
+        ctx.accounts.rental_listing.owner == ctx.accounts.owner.key(),
+        ErrorCode::Unauthorized
+    );
+    require!(ctx.accounts.rental_listing.renter.is_none(), ErrorCode::RentalActive);
+    
+    let marketplace_seeds: &[&[&[u8]]] = &[&[b"marketplace", &[ctx.accounts.marketplace.bump]]];
+    
+    // Transfer token back to owner
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        Transfer {
+            from: ctx.accounts.escrow_token_account.to_account_info(),
+            to: ctx.accounts.owner_token_account.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    transfer(cpi_ctx, 1)?;
+    
+    // Close escrow and return its rent to owner
+    let cpi_ctx = CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.escrow_token_account.to_account_info(),
+            destination: ctx.accounts.owner.to_account_info(),
+            authority: ctx.accounts.marketplace.to_account_info(),
+        },
+        marketplace_seeds,
+    );
+    
+    close_account(cpi_ctx)?;
+    
+    // Update card status
+    let pookie_card = &mut ctx.accounts.pookie_card;
+    pookie_card.is_listed = false;
+    
+    Ok(())
+}
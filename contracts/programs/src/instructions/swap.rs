@@ -0,0 +1,449 @@
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::token::{close_account, CloseAccount, Token, TokenAccount, Transfer, transfer};
+use anchor_spl::associated_token::{self, get_associated_token_address, AssociatedToken};
+
+use crate::state::*;
+use crate::error::ErrorCode;
+
+#[derive(Accounts)]
+#[instruction(swap_id: u64)]
+pub struct CreateSwap<'info> {
+    #[account(mut)]
+    pub initiator: Signer<'info>,
+    
+    #[account(
+        init,
+        payer = initiator,
+        space = 8 + Swap::INIT_SPACE,
+        seeds = [b"swap", initiator.key().as_ref(), swap_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub swap: Account<'info, Swap>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptSwap<'info> {
+    #[account(mut)]
+    pub counterparty: Signer<'info>,
+    
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap", initiator.key().as_ref(), swap.swap_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub swap: Account<'info, Swap>,
+    
+    #[account(
+        seeds = [b"marketplace"],
+        bump
+    )]
+    pub marketplace: Account<'info, Marketplace>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", initiator.key().as_ref()],
+        bump
+    )]
+    pub initiator_stats: Account<'info, UserStats>,
+    
+    #[account(
+        mut,
+        seeds = [b"user_stats", counterparty.key().as_ref()],
+        bump
+    )]
+    pub counterparty_stats: Account<'info, UserStats>,
+    
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelSwap<'info> {
+    /// Either the initiator or the counterparty
+    pub authority: Signer<'info>,
+    
+    #[account(mut)]
+    pub initiator: SystemAccount<'info>,
+    
+    #[account(
+        mut,
+        close = initiator,
+        seeds = [b"swap", initiator.key().as_ref(), swap.swap_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub swap: Account<'info, Swap>,
+    
+    pub token_program: Program<'info, Token>,
+}
+
+pub(crate) fn create_swap<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreateSwap<'info>>,
+    swap_id: u64,
+    counterparty: Pubkey,
+    requested_mints: Vec<Pubkey>,
+    sol_amount: u64,
+) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(counterparty != ctx.accounts.initiator.key(), ErrorCode::InvalidSwap);
+    require!(
+        (1..=Swap::MAX_CARDS).contains(&requested_mints.len()),
+        ErrorCode::InvalidSwap
+    );
+    
+    // Remaining accounts: [card_mint, pookie_card, initiator_token_account, escrow_token_account] per offered card
+    let card_accounts = ctx.remaining_accounts;
+    require!(card_accounts.len() % 4 == 0, ErrorCode::InvalidSwap);
+    let card_count = card_accounts.len() / 4;
+    require!((1..=Swap::MAX_CARDS).contains(&card_count), ErrorCode::InvalidSwap);
+    
+    for (index, mint) in requested_mints.iter().enumerate() {
+        require!(!requested_mints[..index].contains(mint), ErrorCode::InvalidSwap);
+    }
+    
+    let swap_key = ctx.accounts.swap.key();
+    let mut offered_mints = Vec::with_capacity(card_count);
+    
+    for accounts in card_accounts.chunks(4) {
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let initiator_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        let escrow_token_account = &accounts[3];
+        
+        require!(pookie_card.mint == card_mint.key(), ErrorCode::InvalidSwap);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidSwap);
+        require!(!offered_mints.contains(&card_mint.key()), ErrorCode::InvalidSwap);
+        require!(!requested_mints.contains(&card_mint.key()), ErrorCode::InvalidSwap);
+        require!(
+            pookie_card.owner == ctx.accounts.initiator.key(),
+            ErrorCode::NotCardOwner
+        );
+        require!(!pookie_card.is_listed, ErrorCode::AlreadyListed);
+        require!(
+            initiator_token_account.mint == card_mint.key()
+                && initiator_token_account.owner == ctx.accounts.initiator.key(),
+            ErrorCode::InvalidSwap
+        );
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&swap_key, &card_mint.key()),
+            ErrorCode::InvalidSwap
+        );
+        
+        // Create the swap's escrow account for this card
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.initiator.to_account_info(),
+                associated_token: escrow_token_account.clone(),
+                authority: ctx.accounts.swap.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token to escrow
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: initiator_token_account.to_account_info(),
+                to: escrow_token_account.clone(),
+                authority: ctx.accounts.initiator.to_account_info(),
+            },
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Lock the card while it is escrowed
+        pookie_card.is_listed = true;
+        pookie_card.listing_price = None;
+        pookie_card.exit(&crate::ID)?;
+        
+        offered_mints.push(card_mint.key());
+    }
+    
+    // Escrow the optional SOL sweetener in the swap account itself
+    if sol_amount > 0 {
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.initiator.to_account_info(),
+                to: ctx.accounts.swap.to_account_info(),
+            },
+        );
+        
+        system_program::transfer(cpi_ctx, sol_amount)?;
+    }
+    
+    let swap = &mut ctx.accounts.swap;
+    swap.initiator = ctx.accounts.initiator.key();
+    swap.counterparty = counterparty;
+    swap.swap_id = swap_id;
+    swap.offered_mints = offered_mints;
+    swap.requested_mints = requested_mints;
+    swap.sol_amount = sol_amount;
+    swap.created_at = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn accept_swap<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptSwap<'info>>) -> Result<()> {
+    let clock = Clock::get()?;
+    
+    require!(!ctx.accounts.marketplace.is_paused, ErrorCode::MarketplacePaused);
+    require!(
+        ctx.accounts.swap.counterparty == ctx.accounts.counterparty.key(),
+        ErrorCode::Unauthorized
+    );
+    
+    let offered_mints = ctx.accounts.swap.offered_mints.clone();
+    let requested_mints = ctx.accounts.swap.requested_mints.clone();
+    let sol_amount = ctx.accounts.swap.sol_amount;
+    
+    // Remaining accounts, in swap order:
+    // [card_mint, pookie_card, escrow_token_account, counterparty_token_account] per offered card, then
+    // [card_mint, pookie_card, counterparty_token_account, initiator_token_account] per requested card
+    require!(
+        ctx.remaining_accounts.len() == (offered_mints.len() + requested_mints.len()) * 4,
+        ErrorCode::InvalidSwap
+    );
+    let (offered_accounts, requested_accounts) = ctx.remaining_accounts.split_at(offered_mints.len() * 4);
+    
+    let initiator_key = ctx.accounts.initiator.key();
+    let counterparty_key = ctx.accounts.counterparty.key();
+    let swap_key = ctx.accounts.swap.key();
+    let swap_id_bytes = ctx.accounts.swap.swap_id.to_le_bytes();
+    let swap_seeds: &[&[&[u8]]] = &[&[
+        b"swap",
+        initiator_key.as_ref(),
+        swap_id_bytes.as_ref(),
+        &[ctx.bumps.swap],
+    ]];
+    
+    // Release the initiator's escrowed cards to the counterparty
+    for (mint, accounts) in offered_mints.iter().zip(offered_accounts.chunks(4)) {
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let escrow_token_account = &accounts[2];
+        let counterparty_token_account = &accounts[3];
+        
+        require!(card_mint.key() == *mint, ErrorCode::InvalidSwap);
+        require!(pookie_card.mint == *mint, ErrorCode::InvalidSwap);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidSwap);
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&swap_key, mint),
+            ErrorCode::InvalidSwap
+        );
+        
+        // Create the counterparty's token account if needed
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.counterparty.to_account_info(),
+                associated_token: counterparty_token_account.clone(),
+                authority: ctx.accounts.counterparty.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token from escrow to counterparty
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.clone(),
+                to: counterparty_token_account.clone(),
+                authority: ctx.accounts.swap.to_account_info(),
+            },
+            swap_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Close escrow and return its rent to initiator
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.clone(),
+                destination: ctx.accounts.initiator.to_account_info(),
+                authority: ctx.accounts.swap.to_account_info(),
+            },
+            swap_seeds,
+        );
+        
+        close_account(cpi_ctx)?;
+        
+        // Update card ownership
+        pookie_card.owner = counterparty_key;
+        pookie_card.is_listed = false;
+        pookie_card.last_updated = clock.unix_timestamp;
+        pookie_card.exit(&crate::ID)?;
+    }
+    
+    // Move the requested cards from the counterparty to the initiator
+    for (mint, accounts) in requested_mints.iter().zip(requested_accounts.chunks(4)) {
+        let card_mint = &accounts[0];
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[1])?;
+        let counterparty_token_account = &accounts[2];
+        let initiator_token_account = &accounts[3];
+        
+        require!(card_mint.key() == *mint, ErrorCode::InvalidSwap);
+        require!(pookie_card.mint == *mint, ErrorCode::InvalidSwap);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidSwap);
+        require!(pookie_card.owner == counterparty_key, ErrorCode::NotCardOwner);
+        require!(!pookie_card.is_listed, ErrorCode::AlreadyListed);
+        
+        // Create the initiator's token account if needed
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.associated_token_program.to_account_info(),
+            associated_token::Create {
+                payer: ctx.accounts.counterparty.to_account_info(),
+                associated_token: initiator_token_account.clone(),
+                authority: ctx.accounts.initiator.to_account_info(),
+                mint: card_mint.clone(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                token_program: ctx.accounts.token_program.to_account_info(),
+            },
+        );
+        
+        associated_token::create_idempotent(cpi_ctx)?;
+        
+        // Transfer token from counterparty to initiator
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: counterparty_token_account.clone(),
+                to: initiator_token_account.clone(),
+                authority: ctx.accounts.counterparty.to_account_info(),
+            },
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Update card ownership
+        pookie_card.owner = initiator_key;
+        pookie_card.last_updated = clock.unix_timestamp;
+        pookie_card.exit(&crate::ID)?;
+    }
+    
+    // Release escrowed SOL to the counterparty; the swap account is program-owned,
+    // so lamports are moved directly and its rent returns to the initiator on close
+    if sol_amount > 0 {
+        ctx.accounts.swap.sub_lamports(sol_amount)?;
+        ctx.accounts.counterparty.add_lamports(sol_amount)?;
+    }
+    
+    // Update user stats
+    let offered_count = offered_mints.len() as u64;
+    let requested_count = requested_mints.len() as u64;
+    
+    let initiator_stats = &mut ctx.accounts.initiator_stats;
+    initiator_stats.cards_owned = (initiator_stats.cards_owned + requested_count).saturating_sub(offered_count);
+    initiator_stats.total_spent += sol_amount;
+    initiator_stats.last_activity = clock.unix_timestamp;
+    
+    let counterparty_stats = &mut ctx.accounts.counterparty_stats;
+    counterparty_stats.cards_owned = (counterparty_stats.cards_owned + offered_count).saturating_sub(requested_count);
+    counterparty_stats.total_earned += sol_amount;
+    counterparty_stats.last_activity = clock.unix_timestamp;
+    
+    Ok(())
+}
+
+pub(crate) fn cancel_swap<'info>(ctx: Context<'_, '_, 'info, 'info, CancelSwap<'info>>) -> Result<()> {
+    let authority = ctx.accounts.authority.key();
+    require!(
+        authority == ctx.accounts.swap.initiator || authority == ctx.accounts.swap.counterparty,
+        ErrorCode::Unauthorized
+    );
+    
+    let offered_mints = ctx.accounts.swap.offered_mints.clone();
+    
+    // Remaining accounts: [pookie_card, escrow_token_account, initiator_token_account] per offered card
+    require!(
+        ctx.remaining_accounts.len() == offered_mints.len() * 3,
+        ErrorCode::InvalidSwap
+    );
+    
+    let initiator_key = ctx.accounts.initiator.key();
+    let swap_key = ctx.accounts.swap.key();
+    let swap_id_bytes = ctx.accounts.swap.swap_id.to_le_bytes();
+    let swap_seeds: &[&[&[u8]]] = &[&[
+        b"swap",
+        initiator_key.as_ref(),
+        swap_id_bytes.as_ref(),
+        &[ctx.bumps.swap],
+    ]];
+    
+    for (mint, accounts) in offered_mints.iter().zip(ctx.remaining_accounts.chunks(3)) {
+        let mut pookie_card = Account::<PookieCard>::try_from(&accounts[0])?;
+        let escrow_token_account = &accounts[1];
+        let initiator_token_account = Account::<TokenAccount>::try_from(&accounts[2])?;
+        
+        require!(pookie_card.mint == *mint, ErrorCode::InvalidSwap);
+        require!(pookie_card.key() == PookieCard::address(&pookie_card.mint), ErrorCode::InvalidSwap);
+        require!(
+            escrow_token_account.key() == get_associated_token_address(&swap_key, mint),
+            ErrorCode::InvalidSwap
+        );
+        require!(
+            initiator_token_account.mint == *mint && initiator_token_account.owner == initiator_key,
+            ErrorCode::InvalidSwap
+        );
+        
+        // Transfer token back to initiator
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Transfer {
+                from: escrow_token_account.clone(),
+                to: initiator_token_account.to_account_info(),
+                authority: ctx.accounts.swap.to_account_info(),
+            },
+            swap_seeds,
+        );
+        
+        transfer(cpi_ctx, 1)?;
+        
+        // Close escrow and return its rent to initiator
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            CloseAccount {
+                account: escrow_token_account.clone(),
+                destination: ctx.accounts.initiator.to_account_info(),
+                authority: ctx.accounts.swap.to_account_info(),
+            },
+            swap_seeds,
+        );
+        
+        close_account(cpi_ctx)?;
+        
+        // Unlock the card
+        pookie_card.is_listed = false;
+        pookie_card.exit(&crate::ID)?;
+    }
+    
+    // Escrowed SOL returns to the initiator when the swap account closes
+    Ok(())
+}
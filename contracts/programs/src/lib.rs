@@ -20,33 +20,114 @@ pub mod game {
     }
     
     pub fn initialize_user_stats(ctx: Context<InitializeUserStats>) -> Result<()> {
-        initialize_user_stats(ctx)
+        mint_card::initialize_user_stats(ctx)
     }
     
     pub fn initialize_marketplace(ctx: Context<InitializeMarketplace>, fee_percentage: u16) -> Result<()> {
-        initialize_marketplace(ctx, fee_percentage)
+        marketplace::initialize_marketplace(ctx, fee_percentage)
+    }
+    
+    pub fn add_payment_mint(ctx: Context<AddPaymentMint>) -> Result<()> {
+        marketplace::add_payment_mint(ctx)
+    }
+    
+    pub fn remove_payment_mint(ctx: Context<RemovePaymentMint>) -> Result<()> {
+        marketplace::remove_payment_mint(ctx)
+    }
+    
+    pub fn update_marketplace(
+        ctx: Context<UpdateMarketplace>,
+        fee_percentage: Option<u16>,
+        is_paused: Option<bool>,
+        new_authority: Option<Pubkey>,
+        min_bid_increment_bps: Option<u16>,
+        max_bid_increment_bps: Option<u16>,
+    ) -> Result<()> {
+        marketplace::update_marketplace(
+            ctx,
+            fee_percentage,
+            is_paused,
+            new_authority,
+            min_bid_increment_bps,
+            max_bid_increment_bps,
+        )
+    }
+    
+    pub fn accept_marketplace_authority(ctx: Context<AcceptMarketplaceAuthority>) -> Result<()> {
+        marketplace::accept_marketplace_authority(ctx)
+    }
+    
+    pub fn withdraw_fees(ctx: Context<WithdrawFees>, amount: u64) -> Result<()> {
+        marketplace::withdraw_fees(ctx, amount)
+    }
+    
+    pub fn request_mint(ctx: Context<RequestMint>, card_id: u64, name: String) -> Result<()> {
+        mint_card::request_mint(ctx, card_id, name)
     }
     
     pub fn mint_card(
         ctx: Context<MintCard>,
         card_id: u64,
         name: String,
-        rarity: CardRarity,
-        element: CardElement,
+        rarity: Option<CardRarity>,
+        element: Option<CardElement>,
     ) -> Result<()> {
-        handler(ctx, card_id, name, rarity, element)
+        mint_card::handler(ctx, card_id, name, rarity, element)
+    }
+    
+    pub fn migrate_card(ctx: Context<MigrateCard>, card_id: u64) -> Result<()> {
+        card_management::migrate_card(ctx, card_id)
     }
     
     pub fn transfer_card(ctx: Context<TransferCard>) -> Result<()> {
-        transfer_card(ctx)
+        card_management::transfer_card(ctx)
+    }
+    
+    pub fn create_swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateSwap<'info>>,
+        swap_id: u64,
+        counterparty: Pubkey,
+        requested_mints: Vec<Pubkey>,
+        sol_amount: u64,
+    ) -> Result<()> {
+        swap::create_swap(ctx, swap_id, counterparty, requested_mints, sol_amount)
+    }
+    
+    pub fn accept_swap<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptSwap<'info>>) -> Result<()> {
+        swap::accept_swap(ctx)
+    }
+    
+    pub fn cancel_swap<'info>(ctx: Context<'_, '_, 'info, 'info, CancelSwap<'info>>) -> Result<()> {
+        swap::cancel_swap(ctx)
+    }
+    
+    pub fn list_card_for_rent(
+        ctx: Context<ListCardForRent>,
+        price_per_period: u64,
+        period_duration: i64,
+        max_periods: u32,
+    ) -> Result<()> {
+        rental::list_card_for_rent(ctx, price_per_period, period_duration, max_periods)
+    }
+    
+    pub fn rent_card(ctx: Context<RentCard>, periods: u32) -> Result<()> {
+        rental::rent_card(ctx, periods)
+    }
+    
+    pub fn return_rented_card(ctx: Context<ReturnRentedCard>) -> Result<()> {
+        rental::return_rented_card(ctx)
+    }
+    
+    pub fn cancel_rental_listing(ctx: Context<CancelRentalListing>) -> Result<()> {
+        rental::cancel_rental_listing(ctx)
     }
     
     pub fn level_up_card(ctx: Context<LevelUpCard>) -> Result<()> {
-        level_up_card(ctx)
+        card_management::level_up_card(ctx)
     }
     
     pub fn add_experience(ctx: Context<AddExperience>, amount: u32) -> Result<()> {
-        add_experience(ctx, amount)
+        card_management::add_experience(ctx, amount)
     }
     
     pub fn update_card_stats(
@@ -56,7 +137,7 @@ pub mod game {
         new_health: Option<u8>,
         new_special_ability: Option<Option<String>>,
     ) -> Result<()> {
-        update_card_stats(ctx, new_attack, new_defense, new_health, new_special_ability)
+        card_management::update_card_stats(ctx, new_attack, new_defense, new_health, new_special_ability)
     }
     
     pub fn create_collection(
@@ -64,31 +145,225 @@ pub mod game {
         name: String,
         description: String,
         max_supply: Option<u64>,
+        base_uri: String,
+        symbol: String,
+        seller_fee_basis_points: u16,
+    ) -> Result<()> {
+        card_management::create_collection(
+            ctx,
+            name,
+            description,
+            max_supply,
+            base_uri,
+            symbol,
+            seller_fee_basis_points,
+        )
+    }
+    
+    pub fn migrate_collection(
+        ctx: Context<MigrateCollection>,
+        base_uri: String,
+        symbol: String,
+        seller_fee_basis_points: u16,
     ) -> Result<()> {
-        create_collection(ctx, name, description, max_supply)
+        card_management::migrate_collection(ctx, base_uri, symbol, seller_fee_basis_points)
     }
     
-    pub fn list_card(ctx: Context<ListCard>, price: u64) -> Result<()> {
-        list_card(ctx, price)
+    pub fn attach_collection_nft(ctx: Context<AttachCollectionNft>) -> Result<()> {
+        card_management::attach_collection_nft(ctx)
     }
     
-    pub fn buy_card(ctx: Context<BuyCard>) -> Result<()> {
-        buy_card(ctx)
+    pub fn update_collection(
+        ctx: Context<UpdateCollection>,
+        rarity_weights: Option<[u16; 6]>,
+        minter: Option<Option<Pubkey>>,
+        base_uri: Option<String>,
+        symbol: Option<String>,
+        seller_fee_basis_points: Option<u16>,
+    ) -> Result<()> {
+        card_management::update_collection(
+            ctx,
+            rarity_weights,
+            minter,
+            base_uri,
+            symbol,
+            seller_fee_basis_points,
+        )
+    }
+    
+    pub fn list_card(
+        ctx: Context<ListCard>,
+        price: u64,
+        expires_at: Option<i64>,
+        reserved_buyer: Option<Pubkey>,
+    ) -> Result<()> {
+        marketplace::list_card(ctx, price, expires_at, reserved_buyer)
+    }
+    
+    pub fn buy_card<'info>(ctx: Context<'_, '_, 'info, 'info, BuyCard<'info>>) -> Result<()> {
+        marketplace::buy_card(ctx)
     }
     
     pub fn cancel_listing(ctx: Context<CancelListing>) -> Result<()> {
-        cancel_listing(ctx)
+        marketplace::cancel_listing(ctx)
+    }
+    
+    pub fn sweep_listings<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SweepListings<'info>>,
+        max_total_price: u64,
+        skip_invalid: bool,
+    ) -> Result<()> {
+        marketplace::sweep_listings(ctx, max_total_price, skip_invalid)
+    }
+    
+    pub fn update_listing_price(ctx: Context<UpdateListingPrice>, new_price: u64) -> Result<()> {
+        marketplace::update_listing_price(ctx, new_price)
+    }
+    
+    pub fn create_bundle_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreateBundleListing<'info>>,
+        bundle_id: u64,
+        price: u64,
+    ) -> Result<()> {
+        marketplace::create_bundle_listing(ctx, bundle_id, price)
+    }
+    
+    pub fn buy_bundle<'info>(ctx: Context<'_, '_, 'info, 'info, BuyBundle<'info>>) -> Result<()> {
+        marketplace::buy_bundle(ctx)
+    }
+    
+    pub fn cancel_bundle_listing<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CancelBundleListing<'info>>,
+    ) -> Result<()> {
+        marketplace::cancel_bundle_listing(ctx)
     }
     
-    pub fn create_auction(ctx: Context<CreateAuction>, starting_price: u64, duration: i64) -> Result<()> {
-        create_auction(ctx, starting_price, duration)
+    #[allow(clippy::too_many_arguments)]
+    pub fn create_auction(
+        ctx: Context<CreateAuction>,
+        starting_price: u64,
+        duration: i64,
+        extension_window: i64,
+        max_extension: i64,
+        reserve_commitment: Option<[u8; 32]>,
+        buy_now_price: Option<u64>,
+        bid_increment: BidIncrement,
+    ) -> Result<()> {
+        marketplace::create_auction(
+            ctx,
+            starting_price,
+            duration,
+            extension_window,
+            max_extension,
+            reserve_commitment,
+            buy_now_price,
+            bid_increment,
+        )
     }
     
     pub fn place_bid(ctx: Context<PlaceBid>, bid_amount: u64) -> Result<()> {
-        place_bid(ctx, bid_amount)
+        marketplace::place_bid(ctx, bid_amount)
+    }
+    
+    pub fn end_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, EndAuction<'info>>,
+        reserve_reveal: Option<ReserveReveal>,
+    ) -> Result<()> {
+        marketplace::end_auction(ctx, reserve_reveal)
+    }
+    
+    pub fn cancel_auction(ctx: Context<CancelAuction>) -> Result<()> {
+        marketplace::cancel_auction(ctx)
+    }
+    
+    pub fn buy_now<'info>(ctx: Context<'_, '_, 'info, 'info, BuyNow<'info>>) -> Result<()> {
+        marketplace::buy_now(ctx)
+    }
+    
+    pub fn create_sealed_auction(
+        ctx: Context<CreateSealedAuction>,
+        min_price: u64,
+        commit_duration: i64,
+        reveal_duration: i64,
+        pricing: SealedBidPricing,
+        forfeit_unrevealed: bool,
+    ) -> Result<()> {
+        marketplace::create_sealed_auction(
+            ctx,
+            min_price,
+            commit_duration,
+            reveal_duration,
+            pricing,
+            forfeit_unrevealed,
+        )
+    }
+    
+    pub fn commit_sealed_bid(ctx: Context<CommitSealedBid>, commitment: [u8; 32], deposit: u64) -> Result<()> {
+        marketplace::commit_sealed_bid(ctx, commitment, deposit)
+    }
+    
+    pub fn reveal_sealed_bid(ctx: Context<RevealSealedBid>, amount: u64, salt: [u8; 32]) -> Result<()> {
+        marketplace::reveal_sealed_bid(ctx, amount, salt)
+    }
+    
+    pub fn settle_sealed_auction<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SettleSealedAuction<'info>>,
+    ) -> Result<()> {
+        marketplace::settle_sealed_auction(ctx)
+    }
+    
+    pub fn claim_sealed_bid(ctx: Context<ClaimSealedBid>) -> Result<()> {
+        marketplace::claim_sealed_bid(ctx)
+    }
+    
+    pub fn create_dutch_auction(
+        ctx: Context<CreateDutchAuction>,
+        start_price: u64,
+        floor_price: u64,
+        price_decrement: u64,
+        decay_interval: i64,
+    ) -> Result<()> {
+        marketplace::create_dutch_auction(ctx, start_price, floor_price, price_decrement, decay_interval)
+    }
+    
+    pub fn buy_dutch_auction<'info>(ctx: Context<'_, '_, 'info, 'info, BuyDutchAuction<'info>>) -> Result<()> {
+        marketplace::buy_dutch_auction(ctx)
     }
     
-    pub fn end_auction(ctx: Context<EndAuction>) -> Result<()> {
-        end_auction(ctx)
+    pub fn cancel_dutch_auction(ctx: Context<CancelDutchAuction>) -> Result<()> {
+        marketplace::cancel_dutch_auction(ctx)
+    }
+    
+    pub fn make_offer(ctx: Context<MakeOffer>, amount: u64, expires_at: i64) -> Result<()> {
+        marketplace::make_offer(ctx, amount, expires_at)
+    }
+    
+    pub fn cancel_offer(ctx: Context<CancelOffer>) -> Result<()> {
+        marketplace::cancel_offer(ctx)
+    }
+    
+    pub fn accept_offer<'info>(ctx: Context<'_, '_, 'info, 'info, AcceptOffer<'info>>) -> Result<()> {
+        marketplace::accept_offer(ctx)
+    }
+    
+    pub fn create_collection_bid(
+        ctx: Context<CreateCollectionBid>,
+        bid_id: u64,
+        price_per_card: u64,
+        quantity: u64,
+        rarity: Option<CardRarity>,
+        element: Option<CardElement>,
+    ) -> Result<()> {
+        marketplace::create_collection_bid(ctx, bid_id, price_per_card, quantity, rarity, element)
+    }
+    
+    pub fn cancel_collection_bid(ctx: Context<CancelCollectionBid>) -> Result<()> {
+        marketplace::cancel_collection_bid(ctx)
+    }
+    
+    pub fn fill_collection_bid<'info>(
+        ctx: Context<'_, '_, 'info, 'info, FillCollectionBid<'info>>,
+    ) -> Result<()> {
+        marketplace::fill_collection_bid(ctx)
     }
 }
@@ -1,5 +1,6 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{Mint, Token, TokenAccount};
+
+use crate::error::ErrorCode;
 
 #[account]
 pub struct PookieCard {
@@ -19,10 +20,53 @@ pub struct PookieCard {
     pub last_updated: i64,
     pub is_listed: bool,
     pub listing_price: Option<u64>,
+    pub collection: Pubkey,
 }
 
 impl PookieCard {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 4 + 1 + 1 + 1 + 1 + 1 + 4 + 1 + 4 + 8 + 8 + 1 + 9 + 100;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 4 + 1 + 1 + 1 + 1 + 1 + 4 + 1 + 4 + 8 + 8 + 1 + 9 + 32 + 100;
+
+    /// Canonical card address, for card accounts passed in remaining accounts
+    pub fn address(mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"pookie_card", mint.as_ref()], &crate::ID).0
+    }
+
+    pub fn is_controlled_by(&self, player: Pubkey, usage_rights: Option<&UsageRights>, now: i64) -> bool {
+        // Renters control the card for gameplay until their rights expire; owners only
+        // while it is out of escrow, so a lender cannot play a card it has rented out
+        (self.owner == player && !self.is_listed)
+            || usage_rights.is_some_and(|rights| {
+                rights.mint == self.mint && rights.renter == player && rights.is_active(now)
+            })
+    }
+}
+
+#[account]
+pub struct CardIndex {
+    pub card_id: u64,
+    pub mint: Pubkey, // PookieCard lives at [b"pookie_card", mint]; the MintRequest while one holds the id
+}
+
+impl CardIndex {
+    pub const INIT_SPACE: usize = 8 + 32;
+}
+
+#[account]
+pub struct MintRequest {
+    pub payer: Pubkey, // receives the card, whoever completes the mint
+    pub collection: Pubkey,
+    pub card_id: u64,
+    pub name: String,
+    pub slot: u64, // rolls use the first slot hash after this slot
+    pub created_at: i64,
+}
+
+impl MintRequest {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + (4 + Self::MAX_NAME_LEN) + 8 + 8;
+    pub const MAX_NAME_LEN: usize = 32; // Metaplex name limit
+    // Held until the mint is completed, then paid to whoever completes it; covers the
+    // card's rent and Metaplex fees with the rest as a tip for crankers
+    pub const CRANK_DEPOSIT: u64 = 30_000_000;
 }
 
 #[account]
@@ -32,10 +76,29 @@ pub struct Marketplace {
     pub total_volume: u64,
     pub total_sales: u64,
     pub created_at: i64,
+    pub bump: u8,
+    pub total_royalties: u64,
+    pub is_paused: bool,
+    pub pending_authority: Option<Pubkey>, // set by update, cleared on acceptance
+    pub min_bid_increment_bps: u16, // bounds on the increment sellers may require
+    pub max_bid_increment_bps: u16,
 }
 
 impl Marketplace {
-    pub const INIT_SPACE: usize = 32 + 2 + 8 + 8 + 8;
+    pub const INIT_SPACE: usize = 32 + 2 + 8 + 8 + 8 + 1 + 8 + 1 + 33 + 2 + 2;
+
+    pub fn calculate_fee(&self, price: u64) -> u64 {
+        ((price as u128 * self.fee_percentage as u128) / 10000) as u64
+    }
+
+    pub fn allows_bid_increment(&self, increment: &BidIncrement, starting_price: u64) -> bool {
+        // Absolute steps are bounded relative to the starting price
+        let bps = match increment {
+            BidIncrement::BasisPoints(bps) => *bps as u128,
+            BidIncrement::Absolute(step) => (*step as u128 * 10000) / starting_price as u128,
+        };
+        bps >= self.min_bid_increment_bps as u128 && bps <= self.max_bid_increment_bps as u128
+    }
 }
 
 #[account]
@@ -45,10 +108,80 @@ pub struct Listing {
     pub price: u64,
     pub created_at: i64,
     pub is_active: bool,
+    pub payment_mint: Option<Pubkey>, // None = priced in SOL
+    pub expires_at: Option<i64>, // None = never expires
+    pub reserved_buyer: Option<Pubkey>, // None = anyone may buy
 }
 
 impl Listing {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 1;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 1 + 33 + 9 + 33;
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+}
+
+#[account]
+pub struct BundleListing {
+    pub seller: Pubkey,
+    pub bundle_id: u64,
+    pub mints: Vec<Pubkey>,
+    pub price: u64,
+    pub created_at: i64,
+}
+
+impl BundleListing {
+    pub const MAX_CARDS: usize = 10;
+    pub const INIT_SPACE: usize = 32 + 8 + 4 + 32 * Self::MAX_CARDS + 8 + 8;
+}
+
+#[account]
+pub struct Swap {
+    pub initiator: Pubkey,
+    pub counterparty: Pubkey,
+    pub swap_id: u64,
+    pub offered_mints: Vec<Pubkey>,
+    pub requested_mints: Vec<Pubkey>,
+    pub sol_amount: u64, // escrowed by the initiator on top of the offered cards
+    pub created_at: i64,
+}
+
+impl Swap {
+    pub const MAX_CARDS: usize = 5;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + (4 + 32 * Self::MAX_CARDS) * 2 + 8 + 8;
+}
+
+#[account]
+pub struct RentalListing {
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub price_per_period: u64,
+    pub period_duration: i64, // seconds
+    pub max_periods: u32,
+    pub renter: Option<Pubkey>, // set while a rental is running
+    pub created_at: i64,
+}
+
+impl RentalListing {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 4 + 33 + 8;
+    pub const MAX_PERIOD_DURATION: i64 = 365 * 24 * 60 * 60; // one year
+}
+
+#[account]
+pub struct UsageRights {
+    pub renter: Pubkey,
+    pub owner: Pubkey,
+    pub mint: Pubkey,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
+
+impl UsageRights {
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 8 + 8;
+
+    pub fn is_active(&self, now: i64) -> bool {
+        now < self.expires_at
+    }
 }
 
 #[account]
@@ -61,10 +194,154 @@ pub struct Auction {
     pub end_time: i64,
     pub created_at: i64,
     pub is_active: bool,
+    pub extension_window: i64, // bids within this many seconds of end_time extend it
+    pub max_extension: i64,
+    pub total_extension: i64,
+    pub reserve_commitment: Option<[u8; 32]>, // hash of the reserve price and a seller salt
+    pub buy_now_price: Option<u64>,
+    pub payment_mint: Option<Pubkey>, // None = priced in SOL
+    pub bid_increment: BidIncrement,
 }
 
 impl Auction {
-    pub const INIT_SPACE: usize = 32 + 32 + 8 + 9 + 33 + 8 + 8 + 1;
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 9 + 33 + 8 + 8 + 1 + 8 + 8 + 8 + 33 + 9 + 33 + 9;
+    pub const RESERVE_REVEAL_WINDOW: i64 = 24 * 60 * 60; // after end_time, an unrevealed reserve counts as unmet
+
+    pub fn min_next_bid(&self) -> Result<u64> {
+        match self.current_bid {
+            Some(current_bid) => current_bid
+                .checked_add(self.bid_increment.step(current_bid))
+                .ok_or_else(|| error!(ErrorCode::BidOverflow)),
+            None => Ok(self.starting_price),
+        }
+    }
+}
+
+#[account]
+pub struct Offer {
+    pub buyer: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub expires_at: i64,
+    pub created_at: i64,
+}
+
+impl Offer {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 8;
+}
+
+#[account]
+pub struct CollectionBid {
+    pub bidder: Pubkey,
+    pub collection: Pubkey,
+    pub bid_id: u64,
+    pub rarity: Option<CardRarity>,
+    pub element: Option<CardElement>,
+    pub price_per_card: u64,
+    pub quantity_remaining: u64,
+    pub created_at: i64,
+}
+
+impl CollectionBid {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 2 + 2 + 8 + 8 + 8;
+
+    pub fn matches(&self, card: &PookieCard) -> bool {
+        card.collection == self.collection
+            && self.rarity.as_ref().is_none_or(|rarity| *rarity == card.rarity)
+            && self.element.as_ref().is_none_or(|element| *element == card.element)
+    }
+}
+
+#[account]
+pub struct AcceptedPaymentMint {
+    pub mint: Pubkey,
+    pub added_at: i64,
+}
+
+impl AcceptedPaymentMint {
+    pub const INIT_SPACE: usize = 32 + 8;
+}
+
+#[account]
+pub struct SealedAuction {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub min_price: u64,
+    pub commit_end: i64,
+    pub reveal_end: i64,
+    pub pricing: SealedBidPricing,
+    pub forfeit_unrevealed: bool, // unrevealed deposits go to the seller instead of back to the bidder
+    pub highest_bid: Option<u64>,
+    pub highest_bidder: Option<Pubkey>,
+    pub second_bid: Option<u64>,
+    pub open_bids: u32, // bid accounts not yet settled or claimed
+    pub created_at: i64,
+    pub is_settled: bool,
+}
+
+impl SealedAuction {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 8 + 1 + 1 + 9 + 33 + 9 + 4 + 8 + 1;
+
+    pub fn record_reveal(&mut self, bidder: Pubkey, amount: u64) {
+        match self.highest_bid {
+            // Ties go to the earlier reveal
+            Some(highest_bid) if amount <= highest_bid => {
+                if self.second_bid.is_none_or(|second_bid| amount > second_bid) {
+                    self.second_bid = Some(amount);
+                }
+            }
+            _ => {
+                self.second_bid = self.highest_bid;
+                self.highest_bid = Some(amount);
+                self.highest_bidder = Some(bidder);
+            }
+        }
+    }
+
+    pub fn clearing_price(&self) -> Option<u64> {
+        let highest_bid = self.highest_bid?;
+        Some(match self.pricing {
+            SealedBidPricing::FirstPrice => highest_bid,
+            SealedBidPricing::SecondPrice => self.second_bid.unwrap_or(self.min_price),
+        })
+    }
+}
+
+#[account]
+pub struct SealedBid {
+    pub bidder: Pubkey,
+    pub mint: Pubkey,
+    pub commitment: [u8; 32], // sha256(amount_le_bytes || salt)
+    pub deposit: u64,
+    pub revealed_amount: Option<u64>,
+    pub created_at: i64,
+}
+
+impl SealedBid {
+    pub const INIT_SPACE: usize = 32 + 32 + 32 + 8 + 9 + 8;
+}
+
+#[account]
+pub struct DutchAuction {
+    pub seller: Pubkey,
+    pub mint: Pubkey,
+    pub start_price: u64,
+    pub floor_price: u64,
+    pub price_decrement: u64, // lamports dropped every decay_interval
+    pub decay_interval: i64,
+    pub start_time: i64,
+    pub created_at: i64,
+    pub is_active: bool,
+}
+
+impl DutchAuction {
+    pub const INIT_SPACE: usize = 32 + 32 + 8 + 8 + 8 + 8 + 8 + 8 + 1;
+
+    pub fn current_price(&self, now: i64) -> u64 {
+        let intervals_elapsed = (now - self.start_time).max(0) / self.decay_interval;
+        let decay = self.price_decrement.saturating_mul(intervals_elapsed as u64);
+        self.start_price.saturating_sub(decay).max(self.floor_price)
+    }
 }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
@@ -88,7 +365,35 @@ pub enum CardElement {
     Neutral,
 }
 
+/// Seller's opening of an auction's reserve commitment
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReserveReveal {
+    pub price: u64,
+    pub salt: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum BidIncrement {
+    BasisPoints(u16),
+    Absolute(u64), // lamports, or base units of the payment mint
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, PartialEq, Eq)]
+pub enum SealedBidPricing {
+    FirstPrice,
+    SecondPrice,
+}
+
 impl CardRarity {
+    pub const ALL: [CardRarity; 6] = [
+        CardRarity::Common,
+        CardRarity::Uncommon,
+        CardRarity::Rare,
+        CardRarity::Epic,
+        CardRarity::Legendary,
+        CardRarity::Mythic,
+    ];
+
     pub fn max_level(&self) -> u8 {
         match self {
             CardRarity::Common => 10,
@@ -113,7 +418,27 @@ impl CardRarity {
     }
 }
 
+impl BidIncrement {
+    pub fn step(&self, current_bid: u64) -> u64 {
+        match self {
+            // Always require at least one unit so bids strictly increase
+            BidIncrement::BasisPoints(bps) => (((current_bid as u128 * *bps as u128) / 10000) as u64).max(1),
+            BidIncrement::Absolute(step) => *step,
+        }
+    }
+}
+
 impl CardElement {
+    pub const ALL: [CardElement; 7] = [
+        CardElement::Fire,
+        CardElement::Water,
+        CardElement::Earth,
+        CardElement::Air,
+        CardElement::Light,
+        CardElement::Dark,
+        CardElement::Neutral,
+    ];
+
     pub fn advantage_against(&self) -> Option<CardElement> {
         match self {
             CardElement::Fire => Some(CardElement::Earth),
@@ -148,10 +473,73 @@ pub struct CardCollection {
     pub max_supply: Option<u64>,
     pub created_at: i64,
     pub is_active: bool,
+    pub rarity_weights: [u16; 6], // indexed Common through Mythic
+    pub minter: Option<Pubkey>, // may choose rarity and element at mint
+    pub base_uri: String, // card metadata uri is base_uri followed by the card id
+    pub symbol: String,
+    pub seller_fee_basis_points: u16,
+    pub collection_mint: Pubkey, // Metaplex collection NFT, update authority is this PDA
 }
 
 impl CardCollection {
-    pub const INIT_SPACE: usize = 32 + 4 + 50 + 4 + 200 + 8 + 9 + 8 + 1;
+    pub const MAX_BASE_URI_LEN: usize = 180; // leaves room for the card id within Metaplex's 200
+    pub const MAX_SYMBOL_LEN: usize = 10;
+    pub const INIT_SPACE: usize = 32 + 4 + 50 + 4 + 200 + 8 + 9 + 8 + 1 + 12 + 33
+        + 4 + Self::MAX_BASE_URI_LEN + 4 + Self::MAX_SYMBOL_LEN + 2 + 32;
+    pub const DEFAULT_RARITY_WEIGHTS: [u16; 6] = [5000, 2500, 1500, 700, 250, 50];
+
+    pub fn metadata_uri(&self, card_id: u64) -> String {
+        format!("{}{}", self.base_uri, card_id)
+    }
+
+    pub fn collection_uri(&self) -> String {
+        format!("{}collection", self.base_uri)
+    }
+
+    pub fn roll_rarity(&self, roll: u64) -> CardRarity {
+        let total_weight: u64 = self.rarity_weights.iter().map(|weight| *weight as u64).sum();
+        let mut point = roll % total_weight;
+        for (rarity, weight) in CardRarity::ALL.iter().zip(self.rarity_weights) {
+            if point < weight as u64 {
+                return rarity.clone();
+            }
+            point -= weight as u64;
+        }
+        CardRarity::Common
+    }
+}
+
+/// PookieCard as stored before cards recorded their collection
+#[derive(AnchorDeserialize)]
+pub struct LegacyPookieCard {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub card_id: u64,
+    pub name: String,
+    pub rarity: CardRarity,
+    pub element: CardElement,
+    pub attack: u8,
+    pub defense: u8,
+    pub health: u8,
+    pub special_ability: Option<String>,
+    pub level: u8,
+    pub experience: u32,
+    pub created_at: i64,
+    pub last_updated: i64,
+    pub is_listed: bool,
+    pub listing_price: Option<u64>,
+}
+
+/// CardCollection as stored before rarity weights and collection metadata were added
+#[derive(AnchorDeserialize)]
+pub struct LegacyCardCollection {
+    pub authority: Pubkey,
+    pub name: String,
+    pub description: String,
+    pub total_cards: u64,
+    pub max_supply: Option<u64>,
+    pub created_at: i64,
+    pub is_active: bool,
 }
 
 #[account]